@@ -0,0 +1,334 @@
+//! Batch-level summaries over collections of errors.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_derive::Serialize;
+
+use crate::{Error, Result, Severity};
+
+/// Runs through every result of `iter`, splitting it into the successes and
+/// the errors, each in their original order.
+pub fn collect_errors<T, I: IntoIterator<Item = Result<T>>>(iter: I) -> (Vec<T>, Vec<Error>) {
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for result in iter {
+        match result {
+            Ok(value) => values.push(value),
+            Err(err) => errors.push(err),
+        }
+    }
+    (values, errors)
+}
+
+/// Counts how many errors in `errors` carry each reference.
+pub fn count_by_reference(errors: &[Error]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for err in errors {
+        *counts.entry(err.reference.clone()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Counts how many errors in `errors` have each severity.
+///
+/// Severities that do not occur are absent from the map.
+pub fn count_by_severity(errors: &[Error]) -> HashMap<Severity, usize> {
+    let mut counts = HashMap::new();
+    for err in errors {
+        *counts.entry(err.severity).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Per-severity weights used by `health_score`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeverityWeights {
+    pub critical: f64,
+    pub error: f64,
+    pub warning: f64,
+    pub info: f64,
+}
+
+impl SeverityWeights {
+    /// Returns the weight of `severity`.
+    pub fn weight(&self, severity: Severity) -> f64 {
+        match severity {
+            Severity::Critical => self.critical,
+            Severity::Error => self.error,
+            Severity::Warning => self.warning,
+            Severity::Info => self.info,
+        }
+    }
+}
+
+/// Critical=10, Error=5, Warning=2, Info=1.
+impl Default for SeverityWeights {
+    fn default() -> Self {
+        Self {
+            critical: 10.0,
+            error: 5.0,
+            warning: 2.0,
+            info: 1.0,
+        }
+    }
+}
+
+/// Sums the weight of every error in `errors`; an empty batch scores 0.
+pub fn health_score(errors: &[Error], weights: &SeverityWeights) -> f64 {
+    errors.iter().map(|err| weights.weight(err.severity)).sum()
+}
+
+/// Width, in characters, of the longest bar drawn by `severity_histogram`.
+const HISTOGRAM_WIDTH: usize = 20;
+
+/// Renders a text histogram of `errors` per severity, one row per severity.
+///
+/// Rows go from `CRIT` down to `INFO`; each bar is scaled so the most frequent
+/// severity spans the full width, and non-zero counts get at least one block:
+///
+/// ```text
+/// CRIT ████████ 2
+/// ERR  ████████████████████ 5
+/// WARN ████ 1
+/// INFO  0
+/// ```
+pub fn severity_histogram(errors: &[Error]) -> String {
+    let counts = count_by_severity(errors);
+    let max = counts.values().copied().max().unwrap_or(0);
+
+    [
+        Severity::Critical,
+        Severity::Error,
+        Severity::Warning,
+        Severity::Info,
+    ]
+    .iter()
+    .map(|severity| {
+        let count = counts.get(severity).copied().unwrap_or(0);
+        let bar_len = if count == 0 {
+            0
+        } else {
+            (count * HISTOGRAM_WIDTH).div_ceil(max)
+        };
+        format!(
+            "{:<4} {} {}",
+            severity.to_string(),
+            "█".repeat(bar_len),
+            count
+        )
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Identity of an error as compared by its `PartialEq`.
+type IdentityKey = (Severity, String, String, BTreeMap<String, String>);
+
+fn identity_key(err: &Error) -> IdentityKey {
+    (
+        err.severity,
+        err.reference.clone(),
+        err.description.clone(),
+        err.metadata.clone().into_iter().collect(),
+    )
+}
+
+/// One distinct error of an `ErrorGroup` and how many times it was inserted.
+#[derive(Debug, Serialize)]
+pub struct GroupEntry {
+    pub error: Error,
+    pub count: usize,
+}
+
+/// A compact aggregate of a stream of errors: identical errors (see the
+/// `PartialEq` of `Error`) are stored once with a count.
+///
+/// Entries keep the order in which each distinct error was first inserted
+/// and serialize as a list of `{"error": {...}, "count": n}` objects.
+#[derive(Debug, Default, Serialize)]
+#[serde(transparent)]
+pub struct ErrorGroup {
+    entries: Vec<GroupEntry>,
+    #[serde(skip)]
+    index: HashMap<IdentityKey, usize>,
+}
+
+impl ErrorGroup {
+    /// Creates an empty group.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `err`, bumping the count of an identical entry instead of storing
+    /// a repeat. The first occurrence is the one kept.
+    pub fn insert(&mut self, err: Error) {
+        let key = identity_key(&err);
+        match self.index.get(&key) {
+            Some(&i) => self.entries[i].count += 1,
+            None => {
+                self.index.insert(key, self.entries.len());
+                self.entries.push(GroupEntry {
+                    error: err,
+                    count: 1,
+                });
+            }
+        }
+    }
+
+    /// Returns the distinct errors with their counts.
+    pub fn entries(&self) -> &[GroupEntry] {
+        &self.entries
+    }
+
+    /// Returns the number of distinct errors.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if nothing was inserted.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of errors inserted, duplicates included.
+    pub fn total(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+}
+
+impl Extend<Error> for ErrorGroup {
+    fn extend<I: IntoIterator<Item = Error>>(&mut self, iter: I) {
+        for err in iter {
+            self.insert(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch() -> Vec<Error> {
+        vec![
+            Error::new(Severity::Error, "NET-001", "Timeout"),
+            Error::new(Severity::Error, "NET-001", "Timeout"),
+            Error::new(Severity::Warning, "FSY-404", "Cannot read file"),
+            Error::new(Severity::Critical, "NET-001", "Timeout"),
+            Error::new(Severity::Info, "DS-002", "Data parse incomplete"),
+        ]
+    }
+
+    #[test]
+    fn counts_per_reference() {
+        let counts = count_by_reference(&batch());
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts["NET-001"], 3);
+        assert_eq!(counts["FSY-404"], 1);
+        assert_eq!(counts["DS-002"], 1);
+    }
+
+    #[test]
+    fn counts_per_severity() {
+        let counts = count_by_severity(&batch());
+        assert_eq!(counts[&Severity::Error], 2);
+        assert_eq!(counts[&Severity::Warning], 1);
+        assert_eq!(counts[&Severity::Critical], 1);
+        assert_eq!(counts[&Severity::Info], 1);
+        assert!(count_by_severity(&[]).is_empty());
+    }
+
+    #[test]
+    fn histogram_rows_and_counts() {
+        let histogram = severity_histogram(&batch());
+        let rows: Vec<&str> = histogram.lines().collect();
+        assert_eq!(rows.len(), 4);
+        assert!(rows[0].starts_with("CRIT "));
+        assert!(rows[1].starts_with("ERR  "));
+        assert!(rows[2].starts_with("WARN "));
+        assert!(rows[3].starts_with("INFO "));
+
+        assert_eq!(rows[1], format!("ERR  {} 2", "█".repeat(20)));
+        assert_eq!(rows[0], format!("CRIT {} 1", "█".repeat(10)));
+        assert!(rows.iter().all(|row| row.ends_with(" 1") || row.ends_with(" 2")));
+    }
+
+    #[test]
+    fn histogram_empty_input() {
+        let histogram = severity_histogram(&[]);
+        assert_eq!(histogram, "CRIT  0\nERR   0\nWARN  0\nINFO  0");
+    }
+
+    #[test]
+    fn health_score_with_default_weights() {
+        let score = health_score(&batch(), &SeverityWeights::default());
+        assert_eq!(score, 10.0 + 5.0 * 2.0 + 2.0 + 1.0);
+        assert_eq!(health_score(&[], &SeverityWeights::default()), 0.0);
+    }
+
+    #[test]
+    fn health_score_with_custom_weights() {
+        let weights = SeverityWeights {
+            critical: 100.0,
+            ..SeverityWeights::default()
+        };
+        assert_eq!(health_score(&batch(), &weights), 100.0 + 10.0 + 2.0 + 1.0);
+    }
+
+    #[test]
+    fn group_collapses_duplicates_with_counts() {
+        let mut group = ErrorGroup::new();
+        group.extend(batch());
+        group.insert(Error::new(Severity::Error, "NET-001", "Timeout"));
+
+        assert_eq!(group.len(), 4);
+        assert_eq!(group.total(), 6);
+        let counts: Vec<(&str, Severity, usize)> = group
+            .entries()
+            .iter()
+            .map(|entry| (entry.error.reference.as_str(), entry.error.severity, entry.count))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("NET-001", Severity::Error, 3),
+                ("FSY-404", Severity::Warning, 1),
+                ("NET-001", Severity::Critical, 1),
+                ("DS-002", Severity::Info, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn group_distinguishes_metadata_and_serializes_counts() {
+        let mut group = ErrorGroup::new();
+        let timeout = || Error::new(Severity::Error, "NET-001", "Timeout");
+        group.insert(timeout().insert_metadata("host", "db"));
+        group.insert(timeout().insert_metadata("host", "db"));
+        group.insert(timeout().insert_metadata("host", "cache"));
+        assert_eq!(group.len(), 2);
+
+        let json = serde_json::to_value(&group).unwrap();
+        assert_eq!(json[0]["count"], 2);
+        assert_eq!(json[0]["error"]["metadata"]["host"], "db");
+        assert_eq!(json[1]["count"], 1);
+        assert_eq!(json.as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn collect_errors_partitions_results() {
+        let results: Vec<Result<u32>> = vec![
+            Ok(1),
+            Err(Error::new(Severity::Error, "NET-001", "Timeout")),
+            Ok(2),
+            Err(Error::new(Severity::Warning, "FSY-404", "Cannot read file")),
+            Ok(3),
+        ];
+        let (values, errors) = collect_errors(results);
+        assert_eq!(values, [1, 2, 3]);
+        let references: Vec<&str> = errors.iter().map(|err| err.reference.as_str()).collect();
+        assert_eq!(references, ["NET-001", "FSY-404"]);
+
+        let (values, errors) = collect_errors(Vec::<Result<u32>>::new());
+        assert!(values.is_empty() && errors.is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+//! Compact binary encoding with `postcard` (`binary` feature).
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{Error, Severity};
+
+/// Wire layout: postcard is not self-describing, so the optional and
+/// skipped fields of `Error`'s serde form cannot be used as-is.
+#[derive(Serialize, Deserialize)]
+struct Wire {
+    severity: Severity,
+    reference: String,
+    description: String,
+    metadata: Vec<(String, String)>,
+    sources: Vec<String>,
+}
+
+impl Error {
+    /// Encodes the severity, reference, description, metadata and source
+    /// messages with `postcard`. Metadata is written in key order.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, postcard::Error> {
+        let mut metadata: Vec<_> = self
+            .metadata
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        metadata.sort();
+        let mut chain = self.chain();
+        chain.next();
+        let mut sources: Vec<String> = chain.by_ref().map(crate::chain_message).collect();
+        sources.extend(chain.end_marker().map(String::from));
+        postcard::to_allocvec(&Wire {
+            severity: self.severity,
+            reference: self.reference.clone(),
+            description: self.description.clone(),
+            metadata,
+            sources,
+        })
+    }
+
+    /// Decodes an `Error` written by [`Error::to_bytes`].
+    ///
+    /// Sources are restored as a chain of [`crate::MessageError`]s.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, postcard::Error> {
+        let wire: Wire = postcard::from_bytes(bytes)?;
+        Ok(Error::new(wire.severity, wire.reference, wire.description)
+            .with_metadata_map(wire.metadata.into_iter().collect())
+            .with_cause_messages(wire.sources))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_with_metadata_and_source() {
+        let io_err = std::io::Error::other("File not found");
+        let inner = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io_err));
+        let err = Error::new(Severity::Critical, "CFG-001", "Cannot load config")
+            .insert_metadata("filename", "config.toml")
+            .insert_metadata("line", "42")
+            .with_source(Box::new(inner));
+
+        let bytes = err.to_bytes().unwrap();
+        assert!(bytes.len() < serde_json::to_vec(&err).unwrap().len());
+
+        let back = Error::from_bytes(&bytes).unwrap();
+        assert_eq!(back.severity, Severity::Critical);
+        assert_eq!(back.reference, "CFG-001");
+        assert_eq!(back.description, "Cannot load config");
+        assert_eq!(back.metadata, err.metadata);
+        assert_eq!(back.to_string(), err.to_string());
+    }
+
+    #[test]
+    fn garbage_is_rejected() {
+        assert!(Error::from_bytes(&[0xff, 0xff, 0xff]).is_err());
+    }
+}
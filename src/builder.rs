@@ -0,0 +1,133 @@
+//! Deferred construction of errors from templates.
+
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+
+use crate::{Error, Severity};
+
+type BoxedSource = Box<dyn StdError + Send + Sync>;
+
+/// Collects the parts of an `Error` and assembles it on `build`.
+///
+/// Unlike `Error::with_source`, `source_with` takes a closure that only runs
+/// in `build`, so an expensive source is never made for a template that is
+/// dropped unused:
+///
+/// ```
+/// use mcp_error::{ErrorBuilder, Severity};
+///
+/// let template = ErrorBuilder::new(Severity::Error, "CFG-001", "Cannot load config")
+///     .metadata("filename", "config.toml")
+///     .source_with(|| Box::new(std::io::Error::other("File not found")));
+/// let err = template.build();
+/// assert_eq!(err.to_string(), "[ERR] Ref: CFG-001 | Cannot load config | Source: File not found");
+/// ```
+pub struct ErrorBuilder {
+    severity: Severity,
+    reference: String,
+    description: String,
+    metadata: HashMap<String, String>,
+    source: Option<Box<dyn FnOnce() -> BoxedSource + Send>>,
+}
+
+impl ErrorBuilder {
+    /// Starts a builder with the identity of the error.
+    pub fn new<S1, S2>(severity: Severity, reference: S1, description: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self {
+            severity,
+            reference: reference.into(),
+            description: description.into(),
+            metadata: HashMap::new(),
+            source: None,
+        }
+    }
+
+    /// Adds a metadata entry, replacing any previous value for `key`.
+    pub fn metadata<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets an already constructed source.
+    pub fn source(self, source: BoxedSource) -> Self {
+        self.source_with(move || source)
+    }
+
+    /// Sets a closure producing the source; it runs once, in `build`.
+    pub fn source_with<F>(mut self, make_source: F) -> Self
+    where
+        F: FnOnce() -> BoxedSource + Send + 'static,
+    {
+        self.source = Some(Box::new(make_source));
+        self
+    }
+
+    /// Assembles the error, running the source closure if one was set.
+    pub fn build(self) -> Error {
+        let err = Error::new(self.severity, self.reference, self.description)
+            .with_metadata_map(self.metadata);
+        match self.source {
+            Some(make_source) => err.with_source(make_source()),
+            None => err,
+        }
+    }
+}
+
+impl fmt::Debug for ErrorBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorBuilder")
+            .field("severity", &self.severity)
+            .field("reference", &self.reference)
+            .field("description", &self.description)
+            .field("metadata", &self.metadata)
+            .field("source", &self.source.as_ref().map(|_| "<deferred>"))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn source_closure_runs_once_at_build() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let builder = ErrorBuilder::new(Severity::Error, "FSY-404", "Cannot read file")
+            .metadata("filename", "data.json")
+            .source_with(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Box::new(std::io::Error::other("File not found"))
+            });
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let err = builder.build();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(err.metadata["filename"], "data.json");
+        assert_eq!(err.source().unwrap().to_string(), "File not found");
+    }
+
+    #[test]
+    fn dropped_builder_never_runs_closure() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let builder = ErrorBuilder::new(Severity::Info, "NET-100", "Retrying").source_with(
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Box::new(std::io::Error::other("unused"))
+            },
+        );
+        drop(builder);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let err = ErrorBuilder::new(Severity::Info, "NET-100", "Retrying").build();
+        assert!(err.source().is_none());
+    }
+}
@@ -0,0 +1,119 @@
+//! Coarse categories derived from the reference prefix.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+use crate::Error;
+
+/// Label used for references whose prefix has no category.
+pub const OTHER_CATEGORY: &str = "Other";
+
+const DEFAULT_CATEGORIES: &[(&str, &str)] = &[
+    ("NET", "Networking"),
+    ("FSY", "Filesystem"),
+    ("DS", "Data"),
+];
+
+#[derive(Default)]
+struct Registry {
+    overrides: HashMap<String, &'static str>,
+    /// Every label handed out so far, each allocated once.
+    labels: HashSet<&'static str>,
+}
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(Registry::default()))
+}
+
+/// Registers (or replaces) the category label for a reference prefix, e.g.
+/// from categories loaded at runtime.
+///
+/// Registered labels take precedence over the built-in table. They live for
+/// the rest of the process, so `Error::category` can lend them out; each
+/// distinct label is stored once however often it is registered.
+pub fn register_category(prefix: impl Into<String>, label: impl Into<String>) {
+    let label = label.into();
+    let mut registry = registry().write().unwrap_or_else(PoisonError::into_inner);
+    let label = match registry.labels.get(label.as_str()) {
+        Some(&interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(label.into_boxed_str());
+            registry.labels.insert(interned);
+            interned
+        }
+    };
+    registry.overrides.insert(prefix.into(), label);
+}
+
+impl Error {
+    /// Returns a human label for the reference prefix (the part before the first `-`).
+    ///
+    /// `NET-001` is "Networking", `FSY-404` "Filesystem" and `DS-002` "Data"
+    /// unless overridden with `register_category`. Unknown prefixes and
+    /// references without a prefix fall back to `"Other"`.
+    pub fn category(&self) -> &str {
+        let Some((prefix, _)) = self.reference.split_once('-') else {
+            return OTHER_CATEGORY;
+        };
+        if let Some(label) = registry()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .overrides
+            .get(prefix)
+            .copied()
+        {
+            return label;
+        }
+        DEFAULT_CATEGORIES
+            .iter()
+            .find(|(known, _)| *known == prefix)
+            .map_or(OTHER_CATEGORY, |(_, label)| label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Severity;
+
+    fn category_of(reference: &str) -> String {
+        Error::new(Severity::Error, reference, "test").category().to_string()
+    }
+
+    #[test]
+    fn known_prefixes() {
+        assert_eq!(category_of("NET-001"), "Networking");
+        assert_eq!(category_of("FSY-404"), "Filesystem");
+        assert_eq!(category_of("DS-002"), "Data");
+    }
+
+    #[test]
+    fn unknown_and_malformed_references() {
+        assert_eq!(category_of("ZZZ-001"), "Other");
+        assert_eq!(category_of("NET001"), "Other");
+        assert_eq!(category_of(""), "Other");
+        assert_eq!(category_of("-001"), "Other");
+    }
+
+    #[test]
+    fn registered_override() {
+        register_category("BILL", "Billing");
+        assert_eq!(category_of("BILL-042"), "Billing");
+    }
+
+    #[test]
+    fn registered_at_runtime_from_owned_strings() {
+        let loaded = vec![("PAY".to_string(), "Payments".to_string())];
+        for (prefix, label) in loaded {
+            register_category(prefix, label);
+        }
+        assert_eq!(category_of("PAY-001"), "Payments");
+
+        register_category(String::from("PAY"), String::from("Payments"));
+        register_category("PAYOUT", "Payments");
+        let err = Error::new(Severity::Error, "PAYOUT-1", "test");
+        let other = Error::new(Severity::Error, "PAY-1", "test");
+        assert!(std::ptr::eq(err.category(), other.category()));
+    }
+}
@@ -0,0 +1,39 @@
+//! `error-stack` interop (`error-stack` feature).
+
+use error_stack::Report;
+
+use crate::Error;
+
+impl Error {
+    /// Wraps the error in an `error_stack::Report` with this error as its context.
+    #[track_caller]
+    pub fn into_report(self) -> Report<Error> {
+        Report::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Severity;
+
+    #[test]
+    fn report_renders_our_display() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout").insert_metadata("host", "db");
+        let expected = err.to_string();
+
+        let report = err.into_report();
+        assert_eq!(report.current_context().to_string(), expected);
+        assert_eq!(report.current_context().metadata["host"], "db");
+        assert!(format!("{report}").contains("[ERR] Ref: NET-001 | Timeout"));
+    }
+
+    #[test]
+    fn report_from_result() {
+        fn fails() -> Result<(), Report<Error>> {
+            Err(Error::new(Severity::Critical, "SYS-001", "Out of memory").into_report())
+        }
+        let report = fails().unwrap_err();
+        assert_eq!(report.current_context().severity, Severity::Critical);
+    }
+}
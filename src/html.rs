@@ -0,0 +1,121 @@
+//! HTML rendering of `Error` for dashboards (`html` feature).
+
+use std::fmt::Write;
+
+use crate::Error;
+
+/// Escapes the characters that are significant in HTML text and attributes.
+fn escape_html(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+impl Error {
+    /// Renders the error as a small semantic HTML snippet.
+    ///
+    /// Every value is HTML-escaped, metadata is emitted in key order and the
+    /// source chain is wrapped in a collapsible `<details>` element.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let severity = self.severity.to_string();
+
+        let _ = write!(
+            html,
+            "<div class=\"mcp-error severity-{}\">",
+            severity.to_lowercase()
+        );
+        let _ = write!(
+            html,
+            "<span class=\"mcp-error-severity\">{}</span>",
+            escape_html(&severity)
+        );
+        let _ = write!(
+            html,
+            "<span class=\"mcp-error-reference\">{}</span>",
+            escape_html(&self.reference)
+        );
+        let _ = write!(
+            html,
+            "<span class=\"mcp-error-description\">{}</span>",
+            escape_html(&self.description)
+        );
+
+        if !self.metadata.is_empty() {
+            let mut entries: Vec<_> = self.metadata.iter().collect();
+            entries.sort();
+            html.push_str("<table class=\"mcp-error-metadata\">");
+            for (key, value) in entries {
+                let _ = write!(
+                    html,
+                    "<tr><th>{}</th><td>{}</td></tr>",
+                    escape_html(key),
+                    escape_html(value)
+                );
+            }
+            html.push_str("</table>");
+        }
+
+        let mut chain = self.chain();
+        chain.next();
+        let sources: Vec<String> = chain.by_ref().map(crate::chain_message).collect();
+        if !sources.is_empty() || chain.is_truncated() {
+            html.push_str("<details class=\"mcp-error-source\"><summary>Source</summary><ol>");
+            for source in &sources {
+                let _ = write!(html, "<li>{}</li>", escape_html(source));
+            }
+            if let Some(marker) = chain.end_marker() {
+                let _ = write!(html, "<li>{}</li>", escape_html(marker));
+            }
+            html.push_str("</ol></details>");
+        }
+
+        html.push_str("</div>");
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Severity;
+    use std::io;
+
+    #[test]
+    fn html_contains_severity_class() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout");
+        let html = err.to_html();
+        assert!(html.starts_with("<div class=\"mcp-error severity-err\">"));
+        assert!(html.contains("<span class=\"mcp-error-reference\">NET-001</span>"));
+        assert!(html.ends_with("</div>"));
+    }
+
+    #[test]
+    fn html_escapes_values() {
+        let err = Error::new(Severity::Critical, "WEB-001", "<script>alert('x')</script>")
+            .insert_metadata("user", "\"bob\" & co");
+        let html = err.to_html();
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;"));
+        assert!(html.contains("<tr><th>user</th><td>&quot;bob&quot; &amp; co</td></tr>"));
+    }
+
+    #[test]
+    fn html_renders_source_chain() {
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "File not found");
+        let err = Error::new(Severity::Warning, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io_err));
+        let html = err.to_html();
+        assert!(html.contains("<details class=\"mcp-error-source\">"));
+        assert!(html.contains("<li>File not found</li>"));
+    }
+}
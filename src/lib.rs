@@ -8,18 +8,76 @@
 //! - `Result<T> = std::result::Result<T, Error>`
 //! - Crates can attach specific references (like \"NET-001\", \"FSY-404\"), set severity, add metadata, etc.
 
-use std::collections::HashMap;
+// `Error` is deliberately returned by value everywhere; boxing it would only move the cost.
+#![allow(clippy::result_large_err)]
+
+use std::collections::{BTreeMap, HashMap};
+use std::any::{Any, TypeId};
 use std::error::Error as StdError;
 use std::fmt;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use serde::{
     ser::{SerializeStruct},
-    Serializer,
+    Deserializer, Serializer,
+};
+use serde_derive::{Deserialize, Serialize};
+
+#[macro_use]
+mod macros;
+
+#[doc(hidden)]
+pub use macros::__references_unique;
+
+mod aggregate;
+#[cfg(feature = "binary")]
+mod binary;
+mod builder;
+mod category;
+#[cfg(feature = "error-stack")]
+mod error_stack_bridge;
+#[cfg(feature = "html")]
+mod html;
+#[cfg(feature = "log")]
+mod log_bridge;
+mod panic;
+mod registry;
+mod remap;
+mod render;
+mod sampling;
+#[cfg(feature = "schema")]
+mod schema;
+mod sink;
+mod timing;
+#[cfg(feature = "tonic")]
+mod tonic_bridge;
+
+pub use aggregate::{
+    collect_errors, count_by_reference, count_by_severity, health_score, severity_histogram,
+    ErrorGroup, GroupEntry, SeverityWeights,
 };
-use serde_derive::Serialize;
+pub use builder::ErrorBuilder;
+pub use category::{register_category, OTHER_CATEGORY};
+pub use panic::{install_panic_hook, PANIC_REFERENCE};
+pub use registry::{RefInfo, ReferenceRegistry, UNKNOWN_REFERENCE};
+pub use remap::SeverityRemap;
+pub use render::color_enabled;
+pub use sampling::{ErrorSampler, ReferenceRateLimiter};
+pub use sink::SeverityFilterWriter;
+pub use timing::{Stopwatch, ELAPSED_MS_KEY};
 
 /// Indicates how severe an error is.
-#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+///
+/// Serialized as `"Critical"`, `"Error"`, ... by default, or as `"critical"`,
+/// `"error"`, ... with the `snake-case` feature. Formats needing another
+/// representation can pick one in a `serialize_with` function: `name`,
+/// `abbreviation`, `level` (with `from_level` to read it back), `symbol` or
+/// `syslog_code`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "snake-case", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Severity {
     Critical,
     Error,
@@ -29,17 +87,255 @@ pub enum Severity {
 
 impl fmt::Display for Severity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.abbreviation())
+    }
+}
+
+/// Compares against the name or the abbreviation, ignoring ASCII case, like
+/// the `FromStr` implementation: `Severity::Error == "ERR"`.
+impl PartialEq<str> for Severity {
+    fn eq(&self, other: &str) -> bool {
+        self.matches_str(other)
+    }
+}
+
+impl PartialEq<&str> for Severity {
+    fn eq(&self, other: &&str) -> bool {
+        self.matches_str(other)
+    }
+}
+
+/// Severities are ordered by seriousness: `Info < Warning < Error < Critical`.
+impl Ord for Severity {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.level().cmp(&other.level())
+    }
+}
+
+impl PartialOrd for Severity {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Error returned when parsing a `Severity` from an unrecognized string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSeverityError(String);
+
+impl fmt::Display for ParseSeverityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown severity: {:?}", self.0)
+    }
+}
+
+impl StdError for ParseSeverityError {}
+
+/// Strict parsing: accepts the variant names (`"Critical"`, ...) and the
+/// `Display` abbreviations (`"CRIT"`, ...), ignoring ASCII case.
+///
+/// Use `Severity::parse_loose` for input from other systems.
+impl std::str::FromStr for Severity {
+    type Err = ParseSeverityError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        [Severity::Critical, Severity::Error, Severity::Warning, Severity::Info]
+            .into_iter()
+            .find(|severity| severity.matches_str(s))
+            .ok_or_else(|| ParseSeverityError(s.to_string()))
+    }
+}
+
+/// `severity + n` escalates by `n` levels, saturating at `Critical`.
+impl std::ops::Add<i32> for Severity {
+    type Output = Severity;
+
+    fn add(self, steps: i32) -> Severity {
+        self.shift(steps)
+    }
+}
+
+/// `severity - n` de-escalates by `n` levels, saturating at `Info`.
+impl std::ops::Sub<i32> for Severity {
+    type Output = Severity;
+
+    fn sub(self, steps: i32) -> Severity {
+        self.shift(steps.saturating_neg())
+    }
+}
+
+/// ANSI escape sequence resetting the terminal style after `Severity::ansi_code`.
+pub const ANSI_RESET: &str = "\x1b[0m";
+
+impl Severity {
+    /// Numeric rank of the severity: `Info` is 0, up to `Critical` at 3.
+    pub fn level(&self) -> u8 {
+        match self {
+            Severity::Info => 0,
+            Severity::Warning => 1,
+            Severity::Error => 2,
+            Severity::Critical => 3,
+        }
+    }
+
+    /// Inverse of `level`: `0` is `Info`, up to `3` for `Critical`.
+    pub fn from_level(level: u8) -> Option<Severity> {
+        match level {
+            0 => Some(Severity::Info),
+            1 => Some(Severity::Warning),
+            2 => Some(Severity::Error),
+            3 => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+
+    /// Full name of the severity: `"Critical"`, `"Error"`, `"Warning"` or `"Info"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Critical => "Critical",
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+            Severity::Info => "Info",
+        }
+    }
+
+    /// Abbreviation used by `Display`: `"CRIT"`, `"ERR"`, `"WARN"` or `"INFO"`.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Severity::Critical => "CRIT",
+            Severity::Error => "ERR",
+            Severity::Warning => "WARN",
+            Severity::Info => "INFO",
+        }
+    }
+
+    fn matches_str(&self, s: &str) -> bool {
+        s.eq_ignore_ascii_case(self.name()) || s.eq_ignore_ascii_case(self.abbreviation())
+    }
+
+    /// Moves `steps` levels up (positive) or down (negative), saturating at
+    /// `Info` and `Critical`.
+    pub fn shift(self, steps: i32) -> Severity {
+        let level = (i64::from(self.level()) + i64::from(steps)).clamp(0, 3);
+        Severity::from_level(level as u8).expect("level clamped to 0..=3")
+    }
+
+    /// The next more serious severity; `Critical` stays `Critical`.
+    pub fn escalate(self) -> Severity {
+        self.shift(1)
+    }
+
+    /// The next less serious severity; `Info` stays `Info`.
+    pub fn deescalate(self) -> Severity {
+        self.shift(-1)
+    }
+
+    /// Tolerant parsing for levels coming from other systems, e.g. `"fatal"`,
+    /// `"err"`, `"warning"`, `"information"` or `"debug"`.
+    ///
+    /// Matching ignores case and surrounding whitespace; anything unknown is
+    /// treated as `Info`.
+    pub fn parse_loose(s: &str) -> Severity {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "critical" | "crit" | "fatal" | "emerg" | "emergency" | "alert" | "panic" => {
+                Severity::Critical
+            }
+            "error" | "err" | "e" | "severe" | "failure" => Severity::Error,
+            "warning" | "warn" | "w" => Severity::Warning,
+            _ => Severity::Info,
+        }
+    }
+
+    /// Predicate for `Iterator::filter` over `&Error`s keeping those at or
+    /// above `min`: `errors.iter().filter(Severity::at_least_filter(Severity::Error))`.
+    pub fn at_least_filter(min: Severity) -> impl Fn(&&Error) -> bool {
+        move |err| err.severity >= min
+    }
+
+    /// Like `at_least_filter`, keeping only errors of exactly `severity`.
+    pub fn exactly(severity: Severity) -> impl Fn(&&Error) -> bool {
+        move |err| err.severity == severity
+    }
+
+    /// Returns the highest severity yielded by `iter`, or `None` if it is empty.
+    pub fn max_of(iter: impl IntoIterator<Item = Severity>) -> Option<Severity> {
+        iter.into_iter().max()
+    }
+
+    /// Returns the hex color shared by every renderer for this severity.
+    pub fn color_code(&self) -> &'static str {
+        match self {
+            Severity::Critical => "#B00020",
+            Severity::Error => "#D32F2F",
+            Severity::Warning => "#F9A825",
+            Severity::Info => "#1976D2",
+        }
+    }
+
+    /// Returns the ANSI escape sequence matching `color_code` for terminals.
+    pub fn ansi_code(&self) -> &'static str {
         match self {
-            Severity::Critical => write!(f, "CRIT"),
-            Severity::Error => write!(f, "ERR"),
-            Severity::Warning => write!(f, "WARN"),
-            Severity::Info => write!(f, "INFO"),
+            Severity::Critical => "\x1b[1;31m",
+            Severity::Error => "\x1b[31m",
+            Severity::Warning => "\x1b[33m",
+            Severity::Info => "\x1b[34m",
+        }
+    }
+}
+
+/// Reference of the error returned by `Error::new_checked` for an invalid reference.
+pub const INVALID_REFERENCE: &str = "MCP-INVALID-REF";
+
+fn escape_control(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => out.push(c),
         }
     }
+    out
+}
+
+/// A typed value stored in the `fields` of an `Error`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum FieldValue {
+    U64(u64),
+    Bool(bool),
+    Str(String),
 }
 
+/// The core fields of an `Error`, as returned by `Error::into_parts`:
+/// severity, reference, description, metadata and source.
+pub type ErrorParts = (
+    Severity,
+    String,
+    String,
+    HashMap<String, String>,
+    Option<Box<dyn StdError + Send + Sync>>,
+);
+
 /// A minimal, flexible error type for the Ephais ecosystem.
-#[derive(Debug, Serialize)]
+///
+/// Build it with [`Error::new`] (or the severity shortcuts and
+/// [`Error::with_metadata`]) and the `with_*` setters: some fields are private,
+/// so struct literals and exhaustive patterns are not available outside this
+/// crate. `#[non_exhaustive]` keeps it that way should every field become
+/// public, so adding fields is never a breaking change.
+///
+/// Destructure the public fields with a `..` pattern:
+///
+/// ```
+/// # use mcp_error::{Error, Severity};
+/// let Error { severity, reference, .. } = Error::error("NET-001", "Timeout");
+/// assert_eq!((severity, reference.as_str()), (Severity::Error, "NET-001"));
+/// ```
+#[derive(Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Error {
     /// Severity of the error (Error, Warning, Info, etc.).
     pub severity: Severity,
@@ -47,13 +343,72 @@ pub struct Error {
     pub reference: String,
     /// A human-readable error description.
     pub description: String,
-    /// Optional metadata for additional context.
+    /// Optional metadata for additional context, serialized in key order.
+    #[serde(default, serialize_with = "serialize_sorted")]
     pub metadata: HashMap<String, String>,
+    /// Typed, machine-oriented fields, serialized with their native JSON types.
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_sorted"
+    )]
+    fields: HashMap<String, FieldValue>,
+    /// Optional hint of when the caller may retry, serialized as seconds.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_retry_after",
+        deserialize_with = "deserialize_retry_after"
+    )]
+    retry_after: Option<Duration>,
+    /// Optional wall-clock creation time, serialized as Unix milliseconds.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_timestamp",
+        deserialize_with = "deserialize_timestamp"
+    )]
+    timestamp: Option<SystemTime>,
+    /// Process-local creation order, recorded alongside `timestamp`.
+    #[serde(skip)]
+    monotonic_order: Option<u64>,
+    /// Breadcrumbs pushed while the operation progressed, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    context: Vec<String>,
+    /// Optional link to remediation docs for this error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    docs_url: Option<String>,
+    /// Optional HTTP status code associated with the failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    http_status: Option<u16>,
+    /// Optional grouping key for error trackers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fingerprint: Option<String>,
+    /// Typed payloads for handlers that know their type; never serialized.
+    #[serde(skip)]
+    attachments: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
     /// Optional underlying source error.
-    #[serde(serialize_with = "serialize_source")]
+    #[serde(
+        serialize_with = "serialize_source",
+        deserialize_with = "deserialize_source",
+        default
+    )]
     source: Option<Box<dyn StdError + Send + Sync>>,
 }
 
+/// Serializes a map with its keys sorted so the output is deterministic.
+fn serialize_sorted<S, V>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: serde::Serialize,
+{
+    let sorted: BTreeMap<&String, &V> = map.iter().collect();
+    serde::Serialize::serialize(&sorted, serializer)
+}
+
 fn serialize_source<S>(
     source: &Option<Box<dyn StdError + Send + Sync>>,
     serializer: S,
@@ -61,74 +416,256 @@ fn serialize_source<S>(
 where
     S: Serializer,
 {
-    if let Some(source) = source {
-        let mut state = serializer.serialize_struct("Source", 1)?;
-        state.serialize_field("message", &source.to_string())?;
-        state.end()
-    } else {
-        serializer.serialize_none()
+    let Some(source) = source else {
+        return serializer.serialize_none();
+    };
+    let source: &(dyn StdError + 'static) = source.as_ref();
+    let marker = |marker: &str| SourceLevel {
+        type_name: None,
+        message: marker.to_string(),
+    };
+    // The box is already the first source level, hence one level less to walk.
+    let mut levels = Vec::new();
+    match max_chain_depth().checked_sub(1) {
+        Some(depth) => {
+            let mut chain = Chain::new(source, depth);
+            levels.extend(chain.by_ref().map(|err| SourceLevel {
+                type_name: source_type_name(err),
+                message: chain_message(err),
+            }));
+            levels.extend(chain.end_marker().map(marker));
+        }
+        None => levels.push(marker(TRUNCATED_MARKER)),
     }
+    serde::Serialize::serialize(&SourceLevels(&levels), serializer)
 }
 
-impl Error {
-    /// Creates a new `Error` without a source.
-    pub fn new<S1, S2>(severity: Severity, reference: S1, description: S2) -> Self
+/// One serialized chain level.
+struct SourceLevel<'a> {
+    type_name: Option<&'a str>,
+    message: String,
+}
+
+/// Names the concrete type of `err` when it is one of a few well-known error
+/// types; a deserialized `MessageError` reports the name it was read with.
+fn source_type_name<'a>(err: &'a (dyn StdError + 'static)) -> Option<&'a str> {
+    if let Some(err) = err.downcast_ref::<MessageError>() {
+        return err.type_name();
+    }
+    macro_rules! known_types {
+        ($($ty:ty => $name:literal),* $(,)?) => {
+            $(
+                if err.is::<$ty>() {
+                    return Some($name);
+                }
+            )*
+        };
+    }
+    known_types! {
+        Error => "mcp_error::Error",
+        io::Error => "std::io::Error",
+        fmt::Error => "std::fmt::Error",
+        std::env::VarError => "std::env::VarError",
+        std::num::ParseIntError => "std::num::ParseIntError",
+        std::num::ParseFloatError => "std::num::ParseFloatError",
+        std::str::ParseBoolError => "std::str::ParseBoolError",
+        std::str::Utf8Error => "std::str::Utf8Error",
+        std::string::FromUtf8Error => "std::string::FromUtf8Error",
+        serde_json::Error => "serde_json::Error",
+    }
+    None
+}
+
+/// Serializes chain levels as nested `{"type": ..., "message": ..., "source": {...}}`
+/// objects; `type` is present only for types `source_type_name` recognizes.
+struct SourceLevels<'a>(&'a [SourceLevel<'a>]);
+
+impl serde::Serialize for SourceLevels<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
-        S1: Into<String>,
-        S2: Into<String>,
+        S: Serializer,
     {
+        let (level, rest) = self.0.split_first().expect("at least one source level");
+        let len = 1 + usize::from(level.type_name.is_some()) + usize::from(!rest.is_empty());
+        let mut state = serializer.serialize_struct("Source", len)?;
+        if let Some(type_name) = level.type_name {
+            state.serialize_field("type", type_name)?;
+        }
+        state.serialize_field("message", &level.message)?;
+        if !rest.is_empty() {
+            state.serialize_field("source", &SourceLevels(rest))?;
+        }
+        state.end()
+    }
+}
+
+fn serialize_retry_after<S>(
+    retry_after: &Option<Duration>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match retry_after {
+        #[cfg(not(feature = "human-durations"))]
+        Some(duration) => serializer.serialize_f64(duration.as_secs_f64()),
+        #[cfg(feature = "human-durations")]
+        Some(duration) => serde::Serialize::serialize(&HumanDuration::new(*duration), serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// `{"ms": 1500, "human": "1.5s"}` form of a duration (`human-durations` feature).
+#[cfg(feature = "human-durations")]
+#[derive(Serialize)]
+struct HumanDuration {
+    ms: u64,
+    human: String,
+}
+
+#[cfg(feature = "human-durations")]
+impl HumanDuration {
+    fn new(duration: Duration) -> Self {
+        let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        let human = if ms < 1_000 {
+            format!("{}ms", ms)
+        } else if ms < 60_000 {
+            let secs = format!("{:.3}", duration.as_secs_f64());
+            format!("{}s", secs.trim_end_matches('0').trim_end_matches('.'))
+        } else {
+            let secs = duration.as_secs();
+            match (secs / 3600, secs % 3600 / 60, secs % 60) {
+                (0, m, 0) => format!("{}m", m),
+                (0, m, s) => format!("{}m {}s", m, s),
+                (h, 0, _) => format!("{}h", h),
+                (h, m, _) => format!("{}h {}m", h, m),
+            }
+        };
+        Self { ms, human }
+    }
+}
+
+/// Accepted `retry_after` inputs: seconds, or the `human-durations` object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RetryAfterRepr {
+    Secs(f64),
+    Human { ms: u64 },
+}
+
+fn deserialize_retry_after<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let repr: Option<RetryAfterRepr> = serde::Deserialize::deserialize(deserializer)?;
+    repr.map(|repr| match repr {
+        RetryAfterRepr::Secs(secs) => {
+            Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom)
+        }
+        RetryAfterRepr::Human { ms } => Ok(Duration::from_millis(ms)),
+    })
+    .transpose()
+}
+
+fn serialize_timestamp<S>(
+    timestamp: &Option<SystemTime>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match timestamp {
+        Some(time) => {
+            let millis = time
+                .duration_since(UNIX_EPOCH)
+                .map_err(serde::ser::Error::custom)?
+                .as_millis();
+            serializer.serialize_u64(u64::try_from(millis).map_err(serde::ser::Error::custom)?)
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_timestamp<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<SystemTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let millis: Option<u64> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(millis.map(|millis| UNIX_EPOCH + Duration::from_millis(millis)))
+}
+
+/// Descriptions shorter than this are too terse to help on their own.
+pub const LOW_QUALITY_DESCRIPTION_LEN: usize = 12;
+
+/// Lowercased descriptions that say nothing about what went wrong.
+const GENERIC_DESCRIPTIONS: &[&str] = &[
+    "an error occurred",
+    "an unexpected error occurred",
+    "error",
+    "failed",
+    "failure",
+    "internal error",
+    "operation failed",
+    "something went wrong",
+    "unexpected error",
+    "unknown error",
+];
+
+/// Source of `Error::monotonic_order` values, shared by the whole process.
+static NEXT_MONOTONIC_ORDER: AtomicU64 = AtomicU64::new(0);
+
+/// A source error rebuilt from its message alone, e.g. when deserializing an `Error`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageError {
+    message: String,
+    type_name: Option<String>,
+    source: Option<Box<MessageError>>,
+}
+
+impl MessageError {
+    /// Creates a source error carrying only `message`.
+    pub fn new(message: impl Into<String>) -> Self {
         Self {
-            severity,
-            reference: reference.into(),
-            description: description.into(),
-            metadata: HashMap::new(),
+            message: message.into(),
+            type_name: None,
             source: None,
         }
     }
 
-    /// Adds or replaces the source error in an existing `Error`.
-    pub fn with_source(mut self, source: Box<dyn StdError + Send + Sync>) -> Self {
-        self.source = Some(source);
+    /// Records the type name the original source was serialized with.
+    pub fn with_type_name(mut self, type_name: impl Into<String>) -> Self {
+        self.type_name = Some(type_name.into());
         self
     }
 
-    /// Inserts a key/value pair into `metadata`.
-    pub fn insert_metadata<M: Into<String>, N: Into<String>>(mut self, key: M, value: N) -> Self {
-        self.metadata.insert(key.into(), value.into());
+    /// Sets the synthetic error this one was caused by.
+    pub fn with_source(mut self, source: MessageError) -> Self {
+        self.source = Some(Box::new(source));
         self
     }
 
-    /// Returns a reference to the underlying source error, if any.
-    pub fn get_source(&self) -> Option<&(dyn StdError + Send + Sync)> {
-        self.source.as_deref()
+    /// Returns the message of the original source.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the type name of the original source, if it was known.
+    pub fn type_name(&self) -> Option<&str> {
+        self.type_name.as_deref()
     }
 }
 
-impl fmt::Display for Error {
+impl fmt::Display for MessageError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Example output:
-        // [ERR] Ref: NET-001 | description
-        // Optionally show source error: ... | Source: {source}
-        match &self.source {
-            Some(src) => {
-                write!(
-                    f,
-                    "[{}] Ref: {} | {} | Source: {}",
-                    self.severity, self.reference, self.description, src
-                )
-            }
-            None => {
-                write!(
-                    f,
-                    "[{}] Ref: {} | {}",
-                    self.severity, self.reference, self.description
-                )
-            }
-        }
+        write!(f, "{}", self.message)
     }
 }
 
-impl StdError for Error {
+impl StdError for MessageError {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         self.source
             .as_deref()
@@ -136,156 +673,2962 @@ impl StdError for Error {
     }
 }
 
-/// A convenient type alias for results that return `Error`.
-pub type Result<T> = std::result::Result<T, Error>;
-
-pub trait EphErrorExt<T> {
-    /// For non-critical (Info) errors: converts the error into an `Error` with Severity::Info.
-    fn map_mcp_inf(
-        self,
-        reference: impl Into<String>,
-        description: impl Into<String>,
-    ) -> Result<T>;
-
-    /// For Error-level failures: propagate the error.
-    fn map_mcp_err(
-        self,
-        reference: impl Into<String>,
-        description: impl Into<String>,
-    ) -> Result<T>;
+#[derive(Deserialize)]
+struct SourceRepr {
+    #[serde(rename = "type", default)]
+    type_name: Option<String>,
+    message: String,
+    #[serde(default)]
+    source: Option<Box<SourceRepr>>,
+}
 
-    /// For Critical-level failures: propagate the error.
-    fn map_mcp_crit(
-        self,
-        reference: impl Into<String>,
-        description: impl Into<String>,
-    ) -> Result<T>;
+impl From<SourceRepr> for MessageError {
+    fn from(repr: SourceRepr) -> Self {
+        let mut err = MessageError::new(repr.message);
+        err.type_name = repr.type_name;
+        match repr.source {
+            Some(source) => err.with_source(MessageError::from(*source)),
+            None => err,
+        }
+    }
 }
 
-impl<T, E> EphErrorExt<T> for std::result::Result<T, E>
+fn deserialize_source<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<Box<dyn StdError + Send + Sync>>, D::Error>
 where
-    E: std::error::Error + Send + Sync + 'static,
+    D: Deserializer<'de>,
 {
-    fn map_mcp_inf(
-        self,
-        reference: impl Into<String>,
-        description: impl Into<String>,
-    ) -> Result<T> {
-        self.map_err(|e| {
-            Error::new(
-                Severity::Info,
-                reference,
-                format!("{}: {}", description.into(), e),
-            )
-            .with_source(Box::new(e))
-        })
+    let source: Option<SourceRepr> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(source.map(|repr| Box::new(MessageError::from(repr)) as Box<dyn StdError + Send + Sync>))
+}
+
+impl Error {
+    /// Creates a new `Error` without a source.
+    pub fn new<S1, S2>(severity: Severity, reference: S1, description: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self {
+            severity,
+            reference: reference.into(),
+            description: description.into(),
+            metadata: HashMap::new(),
+            fields: HashMap::new(),
+            retry_after: None,
+            timestamp: None,
+            monotonic_order: None,
+            context: Vec::new(),
+            docs_url: None,
+            http_status: None,
+            fingerprint: None,
+            attachments: HashMap::new(),
+            source: None,
+        }
     }
 
-    fn map_mcp_err(
-        self,
+    /// Creates a new `Error` carrying `metadata` from the start.
+    pub fn with_metadata(
+        severity: Severity,
         reference: impl Into<String>,
         description: impl Into<String>,
-    ) -> Result<T> {
-        self.map_err(|e| {
-            Error::new(
-                Severity::Error,
-                reference,
-                format!("{}: {}", description.into(), e),
-            )
-            .with_source(Box::new(e))
-        })
+        metadata: HashMap<String, String>,
+    ) -> Self {
+        Self::new(severity, reference, description).with_metadata_map(metadata)
     }
 
-    fn map_mcp_crit(
-        self,
-        reference: impl Into<String>,
-        description: impl Into<String>,
-    ) -> Result<T> {
-        self.map_err(|e| {
-            Error::new(
-                Severity::Critical,
-                reference,
-                format!("{}: {}", description.into(), e),
-            )
-            .with_source(Box::new(e))
-        })
+    /// Wraps `err` using a classifier that inspects it and picks the severity
+    /// and reference. The description is `err`'s `Display` form and `err`
+    /// becomes the source.
+    pub fn classify<E>(err: E, f: impl FnOnce(&E) -> (Severity, String)) -> Error
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        let (severity, reference) = f(&err);
+        Error::new(severity, reference, err.to_string()).with_source(Box::new(err))
     }
-}
 
-pub trait OrExit<T> {
-    fn or_exit(self) -> T;
-}
+    /// Decomposes the error into its core fields.
+    ///
+    /// Everything else (typed fields, context, retry hint, timestamp, docs
+    /// link, HTTP status, fingerprint, attachments) is dropped.
+    pub fn into_parts(self) -> ErrorParts {
+        (
+            self.severity,
+            self.reference,
+            self.description,
+            self.metadata,
+            self.source,
+        )
+    }
 
-impl<T, E> OrExit<T> for std::result::Result<T, E>
-where
-    E: std::error::Error + Send + Sync + 'static,
-{
-    fn or_exit(self) -> T {
-        match self {
-            Ok(value) => value,
-            Err(err) => {
-                eprintln!("{}", err);
-                std::process::exit(-1);
+    /// Rebuilds an error from the fields returned by `into_parts`.
+    pub fn from_parts(parts: ErrorParts) -> Self {
+        let (severity, reference, description, metadata, source) = parts;
+        Error::new(severity, reference, description)
+            .with_metadata_map(metadata)
+            .with_source_opt(source)
+    }
+
+    /// Creates a new `Severity::Critical` error.
+    pub fn critical(reference: impl Into<String>, description: impl Into<String>) -> Self {
+        Self::new(Severity::Critical, reference, description)
+    }
+
+    /// Creates a new `Severity::Error` error.
+    // Named after the severity, like its siblings, rather than after the type.
+    #[allow(clippy::self_named_constructors)]
+    pub fn error(reference: impl Into<String>, description: impl Into<String>) -> Self {
+        Self::new(Severity::Error, reference, description)
+    }
+
+    /// Creates a new `Severity::Warning` error.
+    pub fn warning(reference: impl Into<String>, description: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, reference, description)
+    }
+
+    /// Creates a new `Severity::Info` error.
+    pub fn info(reference: impl Into<String>, description: impl Into<String>) -> Self {
+        Self::new(Severity::Info, reference, description)
+    }
+
+    /// Creates a new `Error` from untrusted input, keeping it single-line safe.
+    ///
+    /// Policy: a `reference` containing a control character is rejected with an
+    /// `INVALID_REFERENCE` error, while control characters in `description` are
+    /// escaped (`\n`, `\r`, `\t`, or `\u{..}` for the others).
+    pub fn new_checked<S1, S2>(severity: Severity, reference: S1, description: S2) -> Result<Self>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        let reference = reference.into();
+        if reference.chars().any(char::is_control) {
+            return Err(Error::new(
+                Severity::Error,
+                INVALID_REFERENCE,
+                format!("Reference contains control characters: {:?}", reference),
+            ));
+        }
+        Ok(Error::new(
+            severity,
+            reference,
+            escape_control(&description.into()),
+        ))
+    }
+
+    /// Converts a poisoned-lock error into a `Critical` error with reference `"LOCK-POISON"`.
+    ///
+    /// `PoisonError` carries the lock guard, so it cannot be kept as the source;
+    /// its message is folded into the description instead.
+    pub fn from_poison<T>(err: std::sync::PoisonError<T>) -> Error {
+        Error::new(
+            Severity::Critical,
+            "LOCK-POISON",
+            format!("Lock poisoned by a panicking thread: {}", err),
+        )
+    }
+
+    /// Converts a failed subprocess status into a `Severity::Error` with
+    /// reference `"PROC"`.
+    ///
+    /// The metadata holds `command` and, when the process exited normally,
+    /// `exit_code`; on Unix a process killed by a signal gets `signal` instead.
+    pub fn from_exit_status(status: std::process::ExitStatus, command: &str) -> Error {
+        let err = Error::new(
+            Severity::Error,
+            "PROC",
+            format!("Command '{}' failed: {}", command, status),
+        )
+        .insert_metadata("command", command);
+        if let Some(code) = status.code() {
+            return err.insert_metadata("exit_code", code.to_string());
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            if let Some(signal) = status.signal() {
+                return err.insert_metadata("signal", signal.to_string());
+            }
+        }
+        err
+    }
+
+    /// Adds or replaces the source error in an existing `Error`.
+    pub fn with_source(mut self, source: Box<dyn StdError + Send + Sync>) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Like `with_source`, and raises the severity to the source's when the
+    /// source is an `Error` of higher severity, so wrapping cannot hide it.
+    pub fn with_source_promoting(mut self, source: Box<dyn StdError + Send + Sync>) -> Self {
+        if let Some(inner) = source.downcast_ref::<Error>() {
+            self.severity = self.severity.max(inner.severity);
+        }
+        self.with_source(source)
+    }
+
+    /// Combines two independent failures into one: `primary` stays the outer
+    /// error with `secondary` as its source, and the severity becomes the
+    /// higher of the two. Any previous source of `primary` is replaced.
+    pub fn compose(primary: Error, secondary: Error) -> Error {
+        let severity = primary.severity.max(secondary.severity);
+        let mut err = primary.with_source(Box::new(secondary));
+        err.severity = severity;
+        err
+    }
+
+    /// Folds the error of `r` into this error's source: `Err(e)` replaces the
+    /// source with `e` and yields `None`, `Ok(value)` leaves the error untouched
+    /// and yields `Some(value)`.
+    pub fn attach_result<T, E>(self, r: std::result::Result<T, E>) -> (Self, Option<T>)
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        match r {
+            Ok(value) => (self, Some(value)),
+            Err(e) => (self.with_source(Box::new(e)), None),
+        }
+    }
+
+    /// Attaches `source` when it is `Some`; leaves the current source untouched otherwise.
+    pub fn with_source_opt(self, source: Option<Box<dyn StdError + Send + Sync>>) -> Self {
+        match source {
+            Some(source) => self.with_source(source),
+            None => self,
+        }
+    }
+
+    /// Replaces the source with a synthetic chain built from `messages`.
+    ///
+    /// The first message becomes the direct source, the second its source, and
+    /// so on. An empty iterator leaves the current source untouched.
+    pub fn with_cause_messages(self, messages: impl IntoIterator<Item = String>) -> Self {
+        let messages: Vec<String> = messages.into_iter().collect();
+        let chain = messages
+            .into_iter()
+            .rev()
+            .fold(None, |source: Option<MessageError>, message| {
+                let cause = MessageError::new(message);
+                Some(match source {
+                    Some(source) => cause.with_source(source),
+                    None => cause,
+                })
+            });
+        match chain {
+            Some(chain) => self.with_source(Box::new(chain)),
+            None => self,
+        }
+    }
+
+    /// Inserts a key/value pair into `metadata`.
+    pub fn insert_metadata<M: Into<String>, N: Into<String>>(mut self, key: M, value: N) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Shortens every metadata value longer than `max_len` characters to its
+    /// first `max_len - 1` characters followed by `…`, so the result is
+    /// exactly `max_len` characters (`0` empties them). Cuts happen on `char`
+    /// boundaries.
+    pub fn truncate_metadata_values(mut self, max_len: usize) -> Self {
+        for value in self.metadata.values_mut() {
+            if value.chars().count() > max_len {
+                let end = value
+                    .char_indices()
+                    .nth(max_len.saturating_sub(1))
+                    .map_or(value.len(), |(i, _)| i);
+                value.truncate(end);
+                if max_len > 0 {
+                    value.push('…');
+                }
+            }
+        }
+        self
+    }
+
+    /// Returns true if there is at least one metadata entry.
+    pub fn has_metadata(&self) -> bool {
+        !self.metadata.is_empty()
+    }
+
+    /// Returns the number of metadata entries.
+    pub fn metadata_len(&self) -> usize {
+        self.metadata.len()
+    }
+
+    /// Iterates over the metadata keys, in no particular order.
+    pub fn metadata_keys(&self) -> impl Iterator<Item = &str> {
+        self.metadata.keys().map(String::as_str)
+    }
+
+    /// Removes `key` from `metadata`, if present.
+    pub fn remove_metadata(mut self, key: &str) -> Self {
+        self.metadata.remove(key);
+        self
+    }
+
+    /// Keeps only the metadata entries for which `f(key, value)` returns true.
+    pub fn retain_metadata(mut self, f: impl Fn(&str, &str) -> bool) -> Self {
+        self.metadata.retain(|key, value| f(key, value));
+        self
+    }
+
+    /// Inserts every entry of `map` into `metadata`, replacing existing keys.
+    ///
+    /// Pairs well with the [`metadata!`] macro.
+    pub fn with_metadata_map(mut self, map: HashMap<String, String>) -> Self {
+        self.metadata.extend(map);
+        self
+    }
+
+    /// Copies `other`'s metadata into this error with `prefix` prepended to each key.
+    ///
+    /// The prefix is used verbatim, so pass `"inner."` to get `inner.filename`.
+    pub fn merge_metadata_prefixed(mut self, other: &Error, prefix: &str) -> Self {
+        for (key, value) in &other.metadata {
+            self.metadata
+                .insert(format!("{}{}", prefix, key), value.clone());
+        }
+        self
+    }
+
+    /// Stores `value` serialized as a JSON string under `key` in `metadata`.
+    ///
+    /// If `value` cannot be serialized, the metadata is left unchanged.
+    #[cfg(feature = "json")]
+    pub fn with_metadata_json<T: serde::Serialize>(
+        self,
+        key: impl Into<String>,
+        value: &T,
+    ) -> Self {
+        match serde_json::to_string(value) {
+            Ok(json) => self.insert_metadata(key, json),
+            Err(_) => self,
+        }
+    }
+
+    /// Records the current process id and thread name in `metadata`.
+    ///
+    /// Stored under `proc.pid` and `proc.thread`; unnamed threads are recorded
+    /// with their `ThreadId` debug form instead.
+    pub fn with_process_info(self) -> Self {
+        let thread = std::thread::current();
+        let thread_name = match thread.name() {
+            Some(name) => name.to_string(),
+            None => format!("{:?}", thread.id()),
+        };
+        self.insert_metadata("proc.pid", std::process::id().to_string())
+            .insert_metadata("proc.thread", thread_name)
+    }
+
+    /// Sets a typed unsigned integer field.
+    pub fn with_field_u64(mut self, key: impl Into<String>, value: u64) -> Self {
+        self.fields.insert(key.into(), FieldValue::U64(value));
+        self
+    }
+
+    /// Sets a typed boolean field.
+    pub fn with_field_bool(mut self, key: impl Into<String>, value: bool) -> Self {
+        self.fields.insert(key.into(), FieldValue::Bool(value));
+        self
+    }
+
+    /// Sets a typed string field.
+    pub fn with_field_str(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.insert(key.into(), FieldValue::Str(value.into()));
+        self
+    }
+
+    /// Returns the typed field stored under `key`, if any.
+    pub fn field(&self, key: &str) -> Option<&FieldValue> {
+        self.fields.get(key)
+    }
+
+    /// Returns all typed fields.
+    pub fn fields(&self) -> &HashMap<String, FieldValue> {
+        &self.fields
+    }
+
+    /// Pushes a breadcrumb such as "while parsing section [db]".
+    ///
+    /// A lighter alternative to wrapping the error in another `Error`:
+    /// contexts are kept in push order and rendered as a trail after the
+    /// description: `... | Context: while loading config > while parsing section [db]`.
+    pub fn push_context(mut self, msg: impl Into<String>) -> Self {
+        self.context.push(msg.into());
+        self
+    }
+
+    /// Returns the breadcrumbs in the order they were pushed.
+    pub fn context(&self) -> &[String] {
+        &self.context
+    }
+
+    /// Links the error to a remediation page, overriding the URL derived by `docs_url`.
+    pub fn with_docs_url(mut self, url: impl Into<String>) -> Self {
+        self.docs_url = Some(url.into());
+        self
+    }
+
+    /// Returns the docs link: the one set with `with_docs_url`, or else
+    /// `{base}/{reference}`.
+    pub fn docs_url(&self, base: &str) -> String {
+        match &self.docs_url {
+            Some(url) => url.clone(),
+            None => format!("{}/{}", base.trim_end_matches('/'), self.reference),
+        }
+    }
+
+    /// Associates an HTTP status code with the error.
+    pub fn with_http_status(mut self, status: u16) -> Self {
+        self.http_status = Some(status);
+        self
+    }
+
+    /// Returns the HTTP status code, if any.
+    pub fn http_status(&self) -> Option<u16> {
+        self.http_status
+    }
+
+    /// Derives the severity from the HTTP status set with `with_http_status`:
+    /// 500 is `Critical`, other 5xx `Error`, 4xx (429 included) `Warning`, and
+    /// 1xx-3xx `Info`. Without a status, or with one outside 100-599, the
+    /// severity is left unchanged.
+    pub fn with_severity_from_status(mut self) -> Self {
+        self.severity = match self.http_status {
+            Some(500) => Severity::Critical,
+            Some(501..=599) => Severity::Error,
+            Some(400..=499) => Severity::Warning,
+            Some(100..=399) => Severity::Info,
+            _ => self.severity,
+        };
+        self
+    }
+
+    /// Attaches a typed payload, replacing any previous attachment of type `T`.
+    ///
+    /// Attachments are for in-process handlers only: they are not serialized,
+    /// rendered or compared.
+    pub fn with_attachment<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.attachments.insert(TypeId::of::<T>(), Box::new(value));
+        self
+    }
+
+    /// Returns the attachment of type `T`, if any.
+    pub fn attachment<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.attachments
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+    }
+
+    /// Sets a hint of how long the caller should wait before retrying.
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+
+    /// Returns the retry hint, if any.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+
+    /// Records the creation time: the wall clock for display, plus a
+    /// process-wide counter for ordering that clock adjustments cannot break.
+    pub fn with_timestamp_now(mut self) -> Self {
+        self.timestamp = Some(SystemTime::now());
+        self.monotonic_order = Some(NEXT_MONOTONIC_ORDER.fetch_add(1, Ordering::Relaxed));
+        self
+    }
+
+    /// Returns the wall-clock creation time, if recorded.
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        self.timestamp
+    }
+
+    /// Returns the creation order set by `with_timestamp_now`.
+    ///
+    /// Strictly increasing across all errors timestamped in this process,
+    /// unlike the wall clock. Not serialized, as it is meaningless elsewhere.
+    pub fn monotonic_order(&self) -> Option<u64> {
+        self.monotonic_order
+    }
+
+    /// Canonicalizes the error for grouping and deduplication.
+    ///
+    /// The reference is trimmed and uppercased; the description is trimmed and
+    /// every internal run of whitespace is collapsed into a single space.
+    pub fn normalize(mut self) -> Self {
+        self.reference = self.reference.trim().to_uppercase();
+        self.description = self
+            .description
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        self
+    }
+
+    /// Sets the grouping key error trackers should use for this error.
+    pub fn with_fingerprint(mut self, fingerprint: impl Into<String>) -> Self {
+        self.fingerprint = Some(fingerprint.into());
+        self
+    }
+
+    /// Returns the fingerprint set with `with_fingerprint`, or else a stable
+    /// 16-digit hex hash of the reference and description (normalized as by
+    /// `normalize`) and the message of the root cause.
+    ///
+    /// The hash is FNV-1a, so it stays the same across runs and builds.
+    pub fn computed_fingerprint(&self) -> String {
+        if let Some(fingerprint) = &self.fingerprint {
+            return fingerprint.clone();
+        }
+        let reference = self.reference.trim().to_uppercase();
+        let description = self.description.split_whitespace().collect::<Vec<_>>().join(" ");
+        let root_cause = self.chain().skip(1).last().map(chain_message).unwrap_or_default();
+
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for part in [&reference, &description, &root_cause] {
+            // The separator keeps ("ab", "c") and ("a", "bc") apart.
+            for byte in part.bytes().chain([0]) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+        format!("{:016x}", hash)
+    }
+
+    /// Returns a reference to the underlying source error, if any.
+    pub fn get_source(&self) -> Option<&(dyn StdError + Send + Sync)> {
+        self.source.as_deref()
+    }
+
+    /// Replaces the source error, returning the previous one (like `Option::replace`).
+    pub fn replace_source(
+        &mut self,
+        new: Option<Box<dyn StdError + Send + Sync>>,
+    ) -> Option<Box<dyn StdError + Send + Sync>> {
+        std::mem::replace(&mut self.source, new)
+    }
+
+    /// Removes and returns the source error, leaving this error without one.
+    pub fn take_source(&mut self) -> Option<Box<dyn StdError + Send + Sync>> {
+        self.source.take()
+    }
+
+    /// Parses an `Error` back from its JSON serialization.
+    ///
+    /// The source, if any, is restored as a [`MessageError`] holding its message.
+    pub fn from_json(json: &str) -> std::result::Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Iterates over this error followed by each error in its source chain,
+    /// up to `max_chain_depth` sources.
+    pub fn chain(&self) -> Chain<'_> {
+        Chain::new(self, max_chain_depth())
+    }
+
+    /// Like `chain`, with an explicit limit on the number of sources walked.
+    pub fn chain_with_max_depth(&self, max_depth: usize) -> Chain<'_> {
+        Chain::new(self, max_depth)
+    }
+
+    /// Returns true if any error in the chain (this one included) satisfies `pred`.
+    pub fn chain_any<F>(&self, pred: F) -> bool
+    where
+        F: Fn(&(dyn StdError + 'static)) -> bool,
+    {
+        self.chain().any(pred)
+    }
+
+    /// Returns the first error in the chain with the concrete type `E`.
+    pub fn chain_find<E: StdError + 'static>(&self) -> Option<&E> {
+        self.chain().find_map(|err| err.downcast_ref::<E>())
+    }
+
+    /// Returns true if this error or any `Error` in its chain has `reference`.
+    pub fn chain_contains_reference(&self, reference: &str) -> bool {
+        self.chain()
+            .filter_map(|err| err.downcast_ref::<Error>())
+            .any(|err| err.reference == reference)
+    }
+
+    /// Returns the references of the `Error`s in the chain, this one first,
+    /// skipping sources of other types: the subsystems a failure went through.
+    pub fn chain_references(&self) -> Vec<String> {
+        self.chain()
+            .filter_map(|err| err.downcast_ref::<Error>())
+            .map(|err| err.reference.clone())
+            .collect()
+    }
+
+    /// Returns the chain messages, this error's description first, with
+    /// consecutive duplicates collapsed into one.
+    ///
+    /// Useful when libraries wrap an error with its own message at several
+    /// levels. A chain cut short ends with `Chain::end_marker`.
+    pub fn dedup_chain(&self) -> Vec<String> {
+        let mut chain = self.chain();
+        let mut messages: Vec<String> = chain.by_ref().map(chain_message).collect();
+        messages.dedup();
+        messages.extend(chain.end_marker().map(String::from));
+        messages
+    }
+
+    /// Returns true for errors that carry no useful context: no metadata
+    /// (typed fields and breadcrumbs count too), no source, and a description
+    /// that is shorter than `LOW_QUALITY_DESCRIPTION_LEN` characters or a
+    /// generic phrase such as "Something went wrong".
+    ///
+    /// Intended for lints and tests that reject unhelpful errors.
+    pub fn is_low_quality(&self) -> bool {
+        if !self.metadata.is_empty()
+            || !self.fields.is_empty()
+            || !self.context.is_empty()
+            || self.source.is_some()
+        {
+            return false;
+        }
+        let description = self
+            .description
+            .trim()
+            .trim_end_matches(['.', '!'])
+            .to_lowercase();
+        description.chars().count() < LOW_QUALITY_DESCRIPTION_LEN
+            || GENERIC_DESCRIPTIONS.contains(&description.as_str())
+    }
+
+    /// Returns true if the chain holds an `io::Error` worth retrying:
+    /// `TimedOut`, `WouldBlock`, `Interrupted` or `ConnectionReset`.
+    pub fn is_transient_io(&self) -> bool {
+        self.chain_any(|err| {
+            err.downcast_ref::<io::Error>().is_some_and(|io_err| {
+                matches!(
+                    io_err.kind(),
+                    io::ErrorKind::TimedOut
+                        | io::ErrorKind::WouldBlock
+                        | io::ErrorKind::Interrupted
+                        | io::ErrorKind::ConnectionReset
+                )
+            })
+        })
+    }
+}
+
+/// Separator between the parts of the `Display` form.
+const DISPLAY_SEPARATOR: &str = " | ";
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Example output:
+        // [ERR] Ref: NET-001 | description
+        // Optionally show source error: ... | Source: {source}
+        // A source that is itself an `Error` is shown without its [SEV] Ref: prefix.
+        write!(
+            f,
+            "[{}] Ref: {} | {}",
+            self.severity, self.reference, self.description
+        )?;
+        self.fmt_context(f, DISPLAY_SEPARATOR)?;
+        self.fmt_source(f, DISPLAY_SEPARATOR)
+    }
+}
+
+/// Errors are equal when their identity matches: severity, reference,
+/// description and metadata. Sources, typed fields and timestamps are ignored.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        self.severity == other.severity
+            && self.reference == other.reference
+            && self.description == other.description
+            && self.metadata == other.metadata
+    }
+}
+
+impl Eq for Error {}
+
+/// Like a derived `Debug`, except that the source is listed as the levels of
+/// `Error::chain`, so a cyclic chain ends with `CYCLE_MARKER` instead of
+/// recursing forever. `Error` levels show their own fields without a source.
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Error")
+            .field("severity", &self.severity)
+            .field("reference", &self.reference)
+            .field("description", &self.description)
+            .field("metadata", &self.metadata)
+            .field("fields", &self.fields)
+            .field("retry_after", &self.retry_after)
+            .field("timestamp", &self.timestamp)
+            .field("monotonic_order", &self.monotonic_order)
+            .field("context", &self.context)
+            .field("docs_url", &self.docs_url)
+            .field("http_status", &self.http_status)
+            .field("fingerprint", &self.fingerprint)
+            .field("attachments", &self.attachments.len())
+            .field("source", &SourceChainDebug(self))
+            .finish()
+    }
+}
+
+struct SourceChainDebug<'a>(&'a Error);
+
+impl fmt::Debug for SourceChainDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.source.is_none() {
+            return f.write_str("None");
+        }
+        let mut chain = self.0.chain();
+        chain.next();
+        let mut list = f.debug_list();
+        for level in chain.by_ref() {
+            list.entry(&ChainLevelDebug(level));
+        }
+        if let Some(marker) = chain.end_marker() {
+            list.entry(&format_args!("{}", marker));
+        }
+        list.finish()
+    }
+}
+
+struct ChainLevelDebug<'a>(&'a (dyn StdError + 'static));
+
+impl fmt::Debug for ChainLevelDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.downcast_ref::<Error>() {
+            Some(err) => f
+                .debug_struct("Error")
+                .field("severity", &err.severity)
+                .field("reference", &err.reference)
+                .field("description", &err.description)
+                .field("metadata", &err.metadata)
+                .finish_non_exhaustive(),
+            None => fmt::Debug::fmt(self.0, f),
+        }
+    }
+}
+
+impl Error {
+    /// Returns a `Display` adapter rendering this error as someone else's source:
+    /// the description and its own sources, without the severity/reference prefix.
+    pub fn as_source(&self) -> SourceDisplay<'_> {
+        SourceDisplay(self)
+    }
+
+    fn fmt_context(&self, f: &mut fmt::Formatter<'_>, sep: &str) -> fmt::Result {
+        if !self.context.is_empty() {
+            write!(f, "{}Context: {}", sep, self.context.join(" > "))?;
+        }
+        Ok(())
+    }
+
+    /// Writes one `Source:` part per chain level. A nested `Error` shows its
+    /// description followed by its own `Context:` trail, if any, so
+    /// breadcrumbs pushed on an inner error are not lost.
+    fn fmt_source(&self, f: &mut fmt::Formatter<'_>, sep: &str) -> fmt::Result {
+        let mut chain = self.chain();
+        chain.next();
+        for src in chain.by_ref() {
+            match src.downcast_ref::<Error>() {
+                Some(inner) => {
+                    write!(f, "{}Source: {}", sep, inner.description)?;
+                    inner.fmt_context(f, sep)?;
+                }
+                None => write!(f, "{}Source: {}", sep, src)?,
             }
         }
+        if let Some(marker) = chain.end_marker() {
+            write!(f, "{}Source: {}", sep, marker)?;
+        }
+        Ok(())
+    }
+}
+
+/// Message shown for one level of a chain: the description for an `Error`
+/// (its prefix belongs to the outermost error only), `Display` otherwise.
+fn chain_message(err: &(dyn StdError + 'static)) -> String {
+    match err.downcast_ref::<Error>() {
+        Some(inner) => inner.description.clone(),
+        None => err.to_string(),
+    }
+}
+
+/// Prefix-less rendering of an `Error`, returned by [`Error::as_source`].
+pub struct SourceDisplay<'a>(&'a Error);
+
+impl fmt::Display for SourceDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.description)?;
+        self.0.fmt_source(f, DISPLAY_SEPARATOR)
+    }
+}
+
+/// Default number of source levels walked by `Display`, `Error::chain` and serialization.
+pub const DEFAULT_MAX_CHAIN_DEPTH: usize = 16;
+
+/// Marker emitted in place of the sources cut off by the depth limit.
+pub const TRUNCATED_MARKER: &str = "...(truncated)";
+
+/// Marker emitted where a source chain loops back to an error already shown.
+pub const CYCLE_MARKER: &str = "<cycle>";
+
+static MAX_CHAIN_DEPTH: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_CHAIN_DEPTH);
+
+/// Sets how many source levels chain walks follow before truncating.
+pub fn set_max_chain_depth(depth: usize) {
+    MAX_CHAIN_DEPTH.store(depth, Ordering::Relaxed);
+}
+
+/// Returns how many source levels chain walks follow before truncating.
+pub fn max_chain_depth() -> usize {
+    MAX_CHAIN_DEPTH.load(Ordering::Relaxed)
+}
+
+/// Iterator over an error and its sources, returned by [`Error::chain`].
+///
+/// Stops after the depth limit, or when an error already visited shows up
+/// again (a cycle); `is_truncated` then reports that the chain was cut short
+/// and `is_cycle` tells the two apart.
+///
+/// Errors are told apart by address and type (the whole `dyn` pointer), since
+/// a wrapper and a source stored at its offset 0 share an address.
+#[derive(Clone)]
+pub struct Chain<'a> {
+    next: Option<&'a (dyn StdError + 'static)>,
+    seen: Vec<*const (dyn StdError + 'static)>,
+    remaining: usize,
+    truncated: bool,
+    cycle: bool,
+}
+
+impl<'a> Chain<'a> {
+    /// Walks `err` and at most `max_depth` levels of its sources.
+    pub fn new(err: &'a (dyn StdError + 'static), max_depth: usize) -> Self {
+        Self {
+            next: Some(err),
+            seen: Vec::new(),
+            remaining: max_depth.saturating_add(1),
+            truncated: false,
+            cycle: false,
+        }
+    }
+
+    /// Returns true once the walk stopped early because of the depth limit or a cycle.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns true once the walk stopped because an error was revisited.
+    pub fn is_cycle(&self) -> bool {
+        self.cycle
+    }
+
+    /// Marker renderers append for a walk that stopped early:
+    /// `CYCLE_MARKER` for a cycle, `TRUNCATED_MARKER` for the depth limit.
+    pub fn end_marker(&self) -> Option<&'static str> {
+        if self.cycle {
+            Some(CYCLE_MARKER)
+        } else if self.truncated {
+            Some(TRUNCATED_MARKER)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a (dyn StdError + 'static);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+        let ptr = current as *const (dyn StdError + 'static);
+        if self.seen.iter().any(|&seen| std::ptr::eq(seen, ptr)) {
+            self.truncated = true;
+            self.cycle = true;
+            return None;
+        }
+        if self.remaining == 0 {
+            self.truncated = true;
+            return None;
+        }
+        self.remaining -= 1;
+        self.seen.push(ptr);
+        self.next = current.source();
+        Some(current)
+    }
+}
+
+impl TryFrom<&str> for Error {
+    type Error = serde_json::Error;
+
+    fn try_from(json: &str) -> std::result::Result<Self, Self::Error> {
+        Error::from_json(json)
+    }
+}
+
+/// Wraps a boxed error as a `Severity::Error` with reference `"ERR"`,
+/// keeping the box itself as the source.
+impl From<Box<dyn StdError + Send + Sync>> for Error {
+    fn from(source: Box<dyn StdError + Send + Sync>) -> Self {
+        Error::new(Severity::Error, "ERR", source.to_string()).with_source(source)
+    }
+}
+
+/// Renders the error with its `Display` form, for APIs that take `String` errors.
+impl From<Error> for String {
+    fn from(err: Error) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<&Error> for String {
+    fn from(err: &Error) -> Self {
+        err.to_string()
+    }
+}
+
+/// Wraps the error in an `io::Error`, keeping it as the inner error.
+///
+/// The kind is taken from the first `io::Error` in the chain, or `Other`.
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        let kind = err
+            .chain_find::<io::Error>()
+            .map_or(io::ErrorKind::Other, |io_err| io_err.kind());
+        io::Error::new(kind, err)
+    }
+}
+
+/// Wraps a `VarError` as a `Severity::Error` with reference `"ENV"`.
+impl From<std::env::VarError> for Error {
+    fn from(err: std::env::VarError) -> Self {
+        let description = match &err {
+            std::env::VarError::NotPresent => "Environment variable not present",
+            std::env::VarError::NotUnicode(_) => "Environment variable is not valid unicode",
+        };
+        Error::new(Severity::Error, "ENV", description).with_source(Box::new(err))
+    }
+}
+
+/// Inserts each `(key, value)` pair into the metadata, later pairs replacing
+/// earlier values for the same key.
+impl Extend<(String, String)> for Error {
+    fn extend<I: IntoIterator<Item = (String, String)>>(&mut self, iter: I) {
+        self.metadata.extend(iter);
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source
+            .as_deref()
+            .map(|e| e as &(dyn StdError + 'static))
+    }
+}
+
+/// A convenient type alias for results that return `Error`.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Converts any error into an `Error` with a reference and description.
+///
+/// The original error becomes the source, with its message appended to the
+/// description. When it already is an `Error`, it is merged instead of
+/// wrapped, so the chain does not grow a redundant level:
+/// - the new reference replaces the inner one, which is kept in the
+///   `CAUSE_REFERENCE_KEY` metadata (an existing entry, from an earlier
+///   merge, is left alone so the root reference survives);
+/// - the description becomes `"{description}: {inner description}"`;
+/// - the severity is the higher of the two;
+/// - the inner metadata, source and other fields are kept.
+pub trait EphErrorExt<T> {
+    /// For non-critical (Info) errors: converts the error into an `Error` with Severity::Info.
+    fn map_mcp_inf(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T>;
+
+    /// For Error-level failures: propagate the error.
+    fn map_mcp_err(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T>;
+
+    /// For Critical-level failures: propagate the error.
+    fn map_mcp_crit(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T>;
+}
+
+impl<T, E> EphErrorExt<T> for std::result::Result<T, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn map_mcp_inf(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T> {
+        self.map_err(|e| wrap_boxed(Severity::Info, reference, description, Box::new(e)))
+    }
+
+    fn map_mcp_err(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T> {
+        self.map_err(|e| wrap_boxed(Severity::Error, reference, description, Box::new(e)))
+    }
+
+    fn map_mcp_crit(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T> {
+        self.map_err(|e| wrap_boxed(Severity::Critical, reference, description, Box::new(e)))
+    }
+}
+
+/// Builds `"{description}: {err}"` in the caller's description buffer.
+///
+/// Takes ownership of the description string and appends to it, so an owned
+/// `String` is reused instead of copied into a fresh `format!` buffer.
+fn joined_description(description: impl Into<String>, err: &dyn fmt::Display) -> String {
+    use std::fmt::Write as _;
+
+    let mut description = description.into();
+    description.push_str(": ");
+    // Writing into a String cannot fail.
+    let _ = write!(description, "{}", err);
+    description
+}
+
+/// `EphErrorExt` counterpart for results whose error is already boxed.
+///
+/// The boxed error is attached as the source as-is, without wrapping it in
+/// another box, so the chain keeps the original error one level down. A boxed
+/// `Error` is merged following the `EphErrorExt` rules.
+pub trait EphBoxedErrorExt<T> {
+    /// Converts the boxed error into an `Error` with Severity::Info.
+    fn map_mcp_inf(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T>;
+
+    /// Converts the boxed error into an `Error` with Severity::Error.
+    fn map_mcp_err(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T>;
+
+    /// Converts the boxed error into an `Error` with Severity::Critical.
+    fn map_mcp_crit(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T>;
+}
+
+/// Metadata key recording the reference of an `Error` merged by `map_mcp_*`.
+pub const CAUSE_REFERENCE_KEY: &str = "cause.reference";
+
+/// Builds the error returned by the `map_mcp_*` methods, merging a source
+/// that already is an `Error` as described on `EphErrorExt`.
+fn wrap_boxed(
+    severity: Severity,
+    reference: impl Into<String>,
+    description: impl Into<String>,
+    source: Box<dyn StdError + Send + Sync>,
+) -> Error {
+    match source.downcast::<Error>() {
+        Ok(inner) => {
+            let mut err = *inner;
+            let inner_reference = std::mem::replace(&mut err.reference, reference.into());
+            err.description = joined_description(description, &err.description);
+            err.severity = err.severity.max(severity);
+            err.metadata
+                .entry(CAUSE_REFERENCE_KEY.to_string())
+                .or_insert(inner_reference);
+            err
+        }
+        Err(source) => Error::new(
+            severity,
+            reference,
+            joined_description(description, &source),
+        )
+        .with_source(source),
+    }
+}
+
+impl<T> EphBoxedErrorExt<T> for std::result::Result<T, Box<dyn StdError + Send + Sync>> {
+    fn map_mcp_inf(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T> {
+        self.map_err(|e| wrap_boxed(Severity::Info, reference, description, e))
+    }
+
+    fn map_mcp_err(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T> {
+        self.map_err(|e| wrap_boxed(Severity::Error, reference, description, e))
+    }
+
+    fn map_mcp_crit(
+        self,
+        reference: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<T> {
+        self.map_err(|e| wrap_boxed(Severity::Critical, reference, description, e))
+    }
+}
+
+/// Helpers for results that already carry an `Error`.
+pub trait McpResultExt<T> {
+    /// On the error path, inserts a key/value pair into the error's metadata.
+    fn err_meta(self, key: impl Into<String>, value: impl Into<String>) -> Result<T>;
+
+    /// Like `err_meta`, but only computes the pair when there is an error.
+    fn err_meta_with(self, f: impl FnOnce() -> (String, String)) -> Result<T>;
+
+    /// Replaces an error strictly below `threshold` with `Ok(default)`;
+    /// errors at or above it are propagated.
+    fn or_default_below(self, threshold: Severity, default: T) -> Result<T>;
+
+    /// Transforms the error with `f`; `Ok` values pass through untouched.
+    fn attach(self, f: impl FnOnce(Error) -> Error) -> Result<T>;
+
+    /// Lets `f` inspect the error and recover: `Some(value)` becomes `Ok(value)`,
+    /// `None` propagates the original error.
+    fn recover(self, f: impl FnOnce(&Error) -> Option<T>) -> Result<T>;
+
+    /// For loops that should survive non-fatal failures: an error strictly
+    /// below `threshold` is passed to `log` and becomes `Ok(None)`, `Ok(value)`
+    /// becomes `Ok(Some(value))`.
+    ///
+    /// Errors at or above `threshold` are not logged and are returned as-is,
+    /// so the caller aborts with `?`:
+    ///
+    /// ```
+    /// # use mcp_error::{Error, McpResultExt, Result, Severity};
+    /// # fn parse(item: &str) -> Result<u32> {
+    /// #     item.parse().map_err(|_| Error::warning("DS-001", "Not a number"))
+    /// # }
+    /// # fn run(items: &[&str]) -> Result<u32> {
+    /// let mut sum = 0;
+    /// for item in items {
+    ///     let Some(value) = parse(item).log_continue(Severity::Error, |e| eprintln!("{e}"))?
+    ///     else {
+    ///         continue;
+    ///     };
+    ///     sum += value;
+    /// }
+    /// # Ok(sum)
+    /// # }
+    /// # assert_eq!(run(&["1", "x", "2"]).unwrap(), 3);
+    /// ```
+    fn log_continue(self, threshold: Severity, log: impl Fn(&Error)) -> Result<Option<T>>;
+
+    /// For soft failures that should be noted but not propagated: returns the
+    /// value, or passes the error to `log` and returns `default`.
+    ///
+    /// The error reaches `log` unchanged; the closure picks how to record it,
+    /// e.g. `|e| log::warn!("{e}")` to note it at warning level whatever its
+    /// severity.
+    fn warn_and_default(self, default: T, log: impl Fn(&Error)) -> T;
+}
+
+impl<T> McpResultExt<T> for Result<T> {
+    fn err_meta(self, key: impl Into<String>, value: impl Into<String>) -> Result<T> {
+        self.map_err(|e| e.insert_metadata(key, value))
+    }
+
+    fn err_meta_with(self, f: impl FnOnce() -> (String, String)) -> Result<T> {
+        self.map_err(|e| {
+            let (key, value) = f();
+            e.insert_metadata(key, value)
+        })
+    }
+
+    fn or_default_below(self, threshold: Severity, default: T) -> Result<T> {
+        match self {
+            Err(e) if e.severity < threshold => Ok(default),
+            other => other,
+        }
+    }
+
+    fn attach(self, f: impl FnOnce(Error) -> Error) -> Result<T> {
+        self.map_err(f)
+    }
+
+    fn recover(self, f: impl FnOnce(&Error) -> Option<T>) -> Result<T> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(e) => f(&e).ok_or(e),
+        }
+    }
+
+    fn log_continue(self, threshold: Severity, log: impl Fn(&Error)) -> Result<Option<T>> {
+        match self {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.severity < threshold => {
+                log(&e);
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn warn_and_default(self, default: T, log: impl Fn(&Error)) -> T {
+        match self {
+            Ok(value) => value,
+            Err(e) => {
+                log(&e);
+                default
+            }
+        }
+    }
+}
+
+/// Environment variable switching `or_exit` to the detailed `{:#?}` report.
+///
+/// Any non-empty value other than `0` enables it.
+pub const VERBOSE_ENV_VAR: &str = "MCP_ERROR_VERBOSE";
+
+fn verbose_from_env() -> bool {
+    std::env::var(VERBOSE_ENV_VAR)
+        .map(|value| !value.is_empty() && value != "0")
+        .unwrap_or(false)
+}
+
+/// Environment variable holding a global minimum severity, e.g. `WARN`.
+///
+/// Errors below it are not logged by the `emit` helpers, and `or_exit` prints
+/// a one-line notice for them instead of the full report (it still exits, as
+/// it has no value to return).
+///
+/// Parsed with `Severity::from_str`; unset, empty or unparsable values disable
+/// the filter. It is only a default for helpers without a threshold of their
+/// own: an explicit threshold (`log_continue`, `SeverityFilterWriter`, ...)
+/// always takes precedence and the variable is not consulted.
+pub const MIN_SEVERITY_ENV_VAR: &str = "MCP_MIN_SEVERITY";
+
+/// Returns the minimum severity set through `MCP_MIN_SEVERITY`, if any.
+pub fn min_severity_from_env() -> Option<Severity> {
+    std::env::var(MIN_SEVERITY_ENV_VAR)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Returns whether `severity` passes the `MCP_MIN_SEVERITY` filter.
+fn passes_env_filter(severity: Severity) -> bool {
+    min_severity_from_env().is_none_or(|min| severity >= min)
+}
+
+/// Writes the report printed by `or_exit`: `Display` by default, `{:#?}` when verbose.
+fn write_exit_report<W, E>(out: &mut W, err: &E, verbose: bool) -> io::Result<()>
+where
+    W: Write,
+    E: StdError,
+{
+    if verbose {
+        writeln!(out, "{:#?}", err)
+    } else {
+        writeln!(out, "{}", err)
+    }
+}
+
+/// Writes what `or_exit` prints for `err`: the report of `write_exit_report`,
+/// or a one-line notice for an `Error` below `MCP_MIN_SEVERITY`.
+fn write_or_exit_notice<W, E>(out: &mut W, err: &E) -> io::Result<()>
+where
+    W: Write,
+    E: StdError + 'static,
+{
+    match (err as &dyn StdError).downcast_ref::<Error>() {
+        Some(err) if !passes_env_filter(err.severity) => writeln!(
+            out,
+            "[{}] Ref: {} | Exiting (below {})",
+            err.severity, err.reference, MIN_SEVERITY_ENV_VAR
+        ),
+        _ => write_exit_report(out, err, verbose_from_env()),
+    }
+}
+
+pub trait OrExit<T> {
+    /// Returns the value, or prints the error to stderr and exits with code `-1`.
+    ///
+    /// Set `MCP_ERROR_VERBOSE=1` to print the full `Debug` form instead. An
+    /// `Error` below `MCP_MIN_SEVERITY` only gets a one-line notice with its
+    /// severity and reference.
+    fn or_exit(self) -> T;
+}
+
+impl<T, E> OrExit<T> for std::result::Result<T, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn or_exit(self) -> T {
+        match self {
+            Ok(value) => value,
+            Err(err) => {
+                let _ = write_or_exit_notice(&mut io::stderr(), &err);
+                std::process::exit(-1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serializes the tests that set environment variables (`MCP_MIN_SEVERITY`,
+    /// `MCP_ERROR_VERBOSE`, `NO_COLOR`, ...).
+    pub(crate) static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn create_basic_error() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout");
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(err.reference, "NET-001");
+        assert_eq!(err.description, "Timeout");
+        assert!(err.source.is_none());
+    }
+
+    #[test]
+    fn attach_source_error() {
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "File not found");
+        let mcp_err = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io_err));
+
+        // The Display should include the source:
+        let out = format!("{}", mcp_err);
+        assert!(out.contains("Source: File not found"));
+
+        // The source() method should return Some
+        assert!(mcp_err.source().is_some());
+    }
+
+    #[test]
+    fn insert_metadata() {
+        let mut err = Error::new(Severity::Warning, "DS-002", "Data parse incomplete");
+        err = err.insert_metadata("filename", "data.json");
+        err = err.insert_metadata("line", "42");
+
+        assert_eq!(err.metadata["filename"], "data.json");
+        assert_eq!(err.metadata["line"], "42");
+    }
+
+    #[cfg(not(feature = "snake-case"))]
+    #[test]
+    fn serialize_error() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout");
+        let serialized = serde_json::to_string(&err).unwrap();
+        let expected = r#"{"severity":"Error","reference":"NET-001","description":"Timeout","metadata":{},"source":null}"#;
+        assert_eq!(serialized, expected);
+    }
+
+    #[cfg(not(feature = "snake-case"))]
+    #[test]
+    fn serialize_error_with_source() {
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "File not found");
+        let ephais_err = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io_err));
+
+        let serialized = serde_json::to_string(&ephais_err).unwrap();
+        let expected = r#"{"severity":"Error","reference":"FSY-404","description":"Cannot read file","metadata":{},"source":{"type":"std::io::Error","message":"File not found"}}"#;
+        assert_eq!(serialized, expected);
+    }
+
+    #[test]
+    fn exit_report_compact_by_default() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout").insert_metadata("host", "db");
+        let mut out = Vec::new();
+        write_exit_report(&mut out, &err, false).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out, "[ERR] Ref: NET-001 | Timeout\n");
+    }
+
+    #[test]
+    fn exit_report_verbose_includes_metadata_and_source() {
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "File not found");
+        let err = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .insert_metadata("filename", "data.json")
+            .with_source(Box::new(io_err));
+        let mut out = Vec::new();
+        write_exit_report(&mut out, &err, true).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("metadata: {"));
+        assert!(out.contains("\"filename\": \"data.json\""));
+        assert!(out.contains("File not found"));
+    }
+
+    #[test]
+    fn verbose_env_var_toggles_mode() {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::remove_var(VERBOSE_ENV_VAR);
+        assert!(!verbose_from_env());
+
+        std::env::set_var(VERBOSE_ENV_VAR, "1");
+        assert!(verbose_from_env());
+
+        std::env::set_var(VERBOSE_ENV_VAR, "0");
+        assert!(!verbose_from_env());
+
+        std::env::remove_var(VERBOSE_ENV_VAR);
+    }
+
+    #[test]
+    fn min_severity_env_var_filters() {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::remove_var(MIN_SEVERITY_ENV_VAR);
+        assert_eq!(min_severity_from_env(), None);
+        assert!(passes_env_filter(Severity::Info));
+
+        std::env::set_var(MIN_SEVERITY_ENV_VAR, "WARN");
+        assert_eq!(min_severity_from_env(), Some(Severity::Warning));
+        assert!(!passes_env_filter(Severity::Info));
+        assert!(passes_env_filter(Severity::Warning));
+        assert!(passes_env_filter(Severity::Error));
+
+        std::env::set_var(MIN_SEVERITY_ENV_VAR, "loud");
+        assert_eq!(min_severity_from_env(), None);
+        assert!(passes_env_filter(Severity::Info));
+
+        std::env::remove_var(MIN_SEVERITY_ENV_VAR);
+    }
+
+    #[test]
+    fn or_exit_notice_respects_min_severity() {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let notice = |err: &Error| {
+            let mut out = Vec::new();
+            write_or_exit_notice(&mut out, err).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let info = Error::info("CFG-002", "Using defaults");
+        let error = Error::error("CFG-001", "Cannot load config");
+
+        std::env::set_var(MIN_SEVERITY_ENV_VAR, "WARN");
+        assert_eq!(notice(&info), "[INFO] Ref: CFG-002 | Exiting (below MCP_MIN_SEVERITY)\n");
+        assert_eq!(notice(&error), format!("{}\n", error));
+
+        std::env::remove_var(MIN_SEVERITY_ENV_VAR);
+        assert_eq!(notice(&info), format!("{}\n", info));
+    }
+
+    #[test]
+    fn severity_color_codes() {
+        assert_eq!(Severity::Critical.color_code(), "#B00020");
+        assert_eq!(Severity::Error.color_code(), "#D32F2F");
+        assert_eq!(Severity::Warning.color_code(), "#F9A825");
+        assert_eq!(Severity::Info.color_code(), "#1976D2");
+    }
+
+    #[test]
+    fn severity_ansi_codes() {
+        assert_eq!(Severity::Critical.ansi_code(), "\x1b[1;31m");
+        assert_eq!(Severity::Error.ansi_code(), "\x1b[31m");
+        assert_eq!(Severity::Warning.ansi_code(), "\x1b[33m");
+        assert_eq!(Severity::Info.ansi_code(), "\x1b[34m");
+    }
+
+    #[test]
+    fn replace_source_returns_previous() {
+        let first = io::Error::new(io::ErrorKind::NotFound, "first");
+        let second = io::Error::other("second");
+        let mut err = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(first));
+
+        let old = err.replace_source(Some(Box::new(second)));
+        assert_eq!(old.unwrap().to_string(), "first");
+        assert_eq!(err.get_source().unwrap().to_string(), "second");
+
+        let old = err.replace_source(None);
+        assert_eq!(old.unwrap().to_string(), "second");
+        assert!(err.get_source().is_none());
+    }
+
+    #[test]
+    fn parse_serialized_error() {
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "File not found");
+        let original = Error::new(Severity::Warning, "FSY-404", "Cannot read file")
+            .insert_metadata("filename", "data.json")
+            .with_source(Box::new(io_err));
+        let json = serde_json::to_string(&original).unwrap();
+
+        let parsed = Error::try_from(json.as_str()).unwrap();
+        assert_eq!(parsed.severity, Severity::Warning);
+        assert_eq!(parsed.reference, "FSY-404");
+        assert_eq!(parsed.description, "Cannot read file");
+        assert_eq!(parsed.metadata["filename"], "data.json");
+
+        let source = parsed.source().unwrap();
+        assert_eq!(source.to_string(), "File not found");
+        assert!(source.downcast_ref::<MessageError>().is_some());
+    }
+
+    #[cfg(not(feature = "snake-case"))]
+    #[test]
+    fn parse_error_without_source() {
+        let parsed = Error::from_json(r#"{"severity":"Info","reference":"NET-001","description":"Timeout","metadata":{},"source":null}"#).unwrap();
+        assert_eq!(parsed.severity, Severity::Info);
+        assert!(parsed.get_source().is_none());
+
+        assert!(Error::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn map_boxed_error_without_extra_layer() {
+        let boxed: Box<dyn StdError + Send + Sync> =
+            Box::new(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+        let result: std::result::Result<(), _> = Err(boxed);
+
+        let err = result.map_mcp_err("NET-001", "Request failed").unwrap_err();
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(err.description, "Request failed: timed out");
+
+        let source = err.source().unwrap();
+        assert_eq!(
+            source.downcast_ref::<io::Error>().unwrap().kind(),
+            io::ErrorKind::TimedOut
+        );
+        assert!(source.source().is_none());
+    }
+
+    #[test]
+    fn map_boxed_error_severities() {
+        let make = || -> std::result::Result<(), Box<dyn StdError + Send + Sync>> {
+            Err(Box::new(io::Error::other("boom")))
+        };
+        assert_eq!(make().map_mcp_inf("A", "a").unwrap_err().severity, Severity::Info);
+        assert_eq!(make().map_mcp_crit("A", "a").unwrap_err().severity, Severity::Critical);
+    }
+
+    #[test]
+    fn with_source_opt_attaches_some() {
+        let source: Option<Box<dyn StdError + Send + Sync>> =
+            Some(Box::new(io::Error::other("disk full")));
+        let err = Error::new(Severity::Error, "FSY-3", "Can't write file").with_source_opt(source);
+        assert_eq!(err.get_source().unwrap().to_string(), "disk full");
+    }
+
+    #[test]
+    fn with_source_opt_none_is_noop() {
+        let err = Error::new(Severity::Error, "FSY-3", "Can't write file").with_source_opt(None);
+        assert!(err.get_source().is_none());
+
+        let err = Error::new(Severity::Error, "FSY-3", "Can't write file")
+            .with_source(Box::new(io::Error::other("disk full")))
+            .with_source_opt(None);
+        assert_eq!(err.get_source().unwrap().to_string(), "disk full");
+    }
+
+    #[test]
+    fn typed_fields_serialize_natively() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout")
+            .with_field_u64("attempt", 3)
+            .with_field_bool("retryable", true)
+            .with_field_str("host", "db.local");
+        assert_eq!(err.field("attempt"), Some(&FieldValue::U64(3)));
+
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["fields"]["attempt"], serde_json::json!(3));
+        assert_eq!(value["fields"]["retryable"], serde_json::json!(true));
+        assert_eq!(value["fields"]["host"], serde_json::json!("db.local"));
+        assert!(value["metadata"].as_object().unwrap().is_empty());
+    }
+
+    #[test]
+    fn typed_fields_round_trip() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout")
+            .with_field_u64("attempt", 3)
+            .with_field_bool("retryable", false);
+        let parsed = Error::from_json(&serde_json::to_string(&err).unwrap()).unwrap();
+        assert_eq!(parsed.fields(), err.fields());
+    }
+
+    #[test]
+    fn err_meta_passes_ok_through() {
+        let ok: Result<u32> = Ok(7);
+        assert_eq!(ok.err_meta("request_id", "abc").unwrap(), 7);
+
+        let ok: Result<u32> = Ok(7);
+        let value = ok
+            .err_meta_with(|| panic!("must not be computed on Ok"))
+            .unwrap();
+        assert_eq!(value, 7);
+    }
+
+    #[test]
+    fn err_meta_attaches_on_error() {
+        let failed: Result<()> = Err(Error::new(Severity::Error, "NET-001", "Timeout"));
+        let err = failed
+            .err_meta("request_id", "abc")
+            .err_meta_with(|| ("attempt".to_string(), "2".to_string()))
+            .unwrap_err();
+        assert_eq!(err.metadata["request_id"], "abc");
+        assert_eq!(err.metadata["attempt"], "2");
+    }
+
+    #[test]
+    fn chain_yields_error_then_sources() {
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "File not found");
+        let inner = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io_err));
+        let outer = Error::new(Severity::Error, "CFG-001", "Cannot load config")
+            .with_source(Box::new(inner));
+
+        assert_eq!(outer.chain().count(), 3);
+        assert_eq!(outer.chain().last().unwrap().to_string(), "File not found");
+    }
+
+    #[test]
+    fn chain_scan_finds_nested_timeout() {
+        let io_err = io::Error::new(io::ErrorKind::TimedOut, "timed out");
+        let inner = Error::new(Severity::Error, "NET-001", "Request failed")
+            .with_source(Box::new(io_err));
+        let outer = Error::new(Severity::Error, "API-002", "Sync failed")
+            .with_source(Box::new(inner));
+
+        assert!(outer.chain_any(|e| {
+            e.downcast_ref::<io::Error>()
+                .is_some_and(|io| io.kind() == io::ErrorKind::TimedOut)
+        }));
+        assert!(!outer.chain_any(|e| {
+            e.downcast_ref::<io::Error>()
+                .is_some_and(|io| io.kind() == io::ErrorKind::NotFound)
+        }));
+
+        let found = outer.chain_find::<io::Error>().unwrap();
+        assert_eq!(found.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(outer.chain_find::<Error>().unwrap().reference, "API-002");
+        assert!(outer.chain_find::<fmt::Error>().is_none());
+    }
+
+    #[test]
+    fn normalize_cleans_messy_input() {
+        let err = Error::new(
+            Severity::Error,
+            "  net-001 ",
+            "\t Connection   timed\n out  ",
+        )
+        .normalize();
+        assert_eq!(err.reference, "NET-001");
+        assert_eq!(err.description, "Connection timed out");
+    }
+
+    #[test]
+    fn normalize_keeps_clean_input() {
+        let err = Error::new(Severity::Warning, "FSY-404", "Cannot read file").normalize();
+        assert_eq!(err.severity, Severity::Warning);
+        assert_eq!(err.reference, "FSY-404");
+        assert_eq!(err.description, "Cannot read file");
+    }
+
+    #[test]
+    fn nested_error_source_has_no_duplicate_prefix() {
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "File not found");
+        let inner = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io_err));
+        let outer = Error::new(Severity::Critical, "CFG-001", "Cannot load config")
+            .with_source(Box::new(inner));
+
+        let out = outer.to_string();
+        assert_eq!(
+            out,
+            "[CRIT] Ref: CFG-001 | Cannot load config | Source: Cannot read file | Source: File not found"
+        );
+        assert_eq!(out.matches("Ref:").count(), 1);
+    }
+
+    #[test]
+    fn as_source_omits_prefix() {
+        let err = Error::new(Severity::Error, "FSY-404", "Cannot read file");
+        assert_eq!(err.as_source().to_string(), "Cannot read file");
+    }
+
+    #[test]
+    fn severity_ordering() {
+        assert!(Severity::Info < Severity::Warning);
+        assert!(Severity::Warning < Severity::Error);
+        assert!(Severity::Error < Severity::Critical);
+    }
+
+    #[test]
+    fn severity_max_of() {
+        assert_eq!(Severity::max_of(Vec::new()), None);
+        assert_eq!(
+            Severity::max_of([Severity::Info, Severity::Error, Severity::Warning]),
+            Some(Severity::Error)
+        );
+        assert_eq!(
+            Severity::max_of([Severity::Critical, Severity::Info]),
+            Some(Severity::Critical)
+        );
+    }
+
+    #[test]
+    fn process_info_records_pid_and_thread() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout").with_process_info();
+        assert_eq!(err.metadata["proc.pid"], std::process::id().to_string());
+
+        let err = std::thread::Builder::new()
+            .name("worker-1".into())
+            .spawn(|| Error::new(Severity::Error, "NET-001", "Timeout").with_process_info())
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(err.metadata["proc.thread"], "worker-1");
+    }
+
+    #[test]
+    fn from_boxed_error() {
+        let boxed: Box<dyn StdError + Send + Sync> =
+            Box::new(io::Error::new(io::ErrorKind::NotFound, "File not found"));
+        let err = Error::from(boxed);
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(err.reference, "ERR");
+        assert_eq!(err.description, "File not found");
+        assert!(err.source().unwrap().downcast_ref::<io::Error>().is_some());
+    }
+
+    #[test]
+    fn question_mark_from_boxed_error() {
+        fn boxed() -> std::result::Result<(), Box<dyn StdError + Send + Sync>> {
+            Err("bad input".into())
+        }
+        fn wrapped() -> Result<()> {
+            boxed()?;
+            Ok(())
+        }
+        assert_eq!(wrapped().unwrap_err().description, "bad input");
+    }
+
+    #[test]
+    fn cause_messages_build_ordered_chain() {
+        let err = Error::new(Severity::Error, "SYNC-001", "Remote sync failed").with_cause_messages(
+            ["upstream rejected", "quota exceeded", "disk full"].map(String::from),
+        );
+
+        let causes: Vec<String> = err.chain().skip(1).map(|e| e.to_string()).collect();
+        assert_eq!(causes, ["upstream rejected", "quota exceeded", "disk full"]);
+        assert!(err.chain_find::<MessageError>().is_some());
+    }
+
+    #[test]
+    fn cause_messages_empty_keeps_source() {
+        let err = Error::new(Severity::Error, "SYNC-001", "Remote sync failed")
+            .with_source(Box::new(io::Error::other("offline")))
+            .with_cause_messages(Vec::new());
+        assert_eq!(err.get_source().unwrap().to_string(), "offline");
+    }
+
+    #[test]
+    fn transient_io_detection() {
+        let timed_out = Error::new(Severity::Error, "NET-001", "Request failed")
+            .with_source(Box::new(io::Error::new(io::ErrorKind::TimedOut, "timed out")));
+        assert!(timed_out.is_transient_io());
+
+        let wrapped = Error::new(Severity::Error, "API-002", "Sync failed")
+            .with_source(Box::new(timed_out));
+        assert!(wrapped.is_transient_io());
+
+        let not_found = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io::Error::new(io::ErrorKind::NotFound, "missing")));
+        assert!(!not_found.is_transient_io());
+
+        assert!(!Error::new(Severity::Error, "NET-001", "Timeout").is_transient_io());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn metadata_json_round_trips() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Request {
+            id: u32,
+            path: String,
+        }
+
+        let request = Request {
+            id: 7,
+            path: "/v1/items".into(),
+        };
+        let err = Error::new(Severity::Error, "API-002", "Request failed")
+            .with_metadata_json("request", &request);
+
+        let stored: Request = serde_json::from_str(&err.metadata["request"]).unwrap();
+        assert_eq!(stored, request);
+    }
+
+    #[test]
+    fn take_source_moves_it_out() {
+        let mut err = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io::Error::new(io::ErrorKind::NotFound, "File not found")));
+
+        let source = err.take_source().unwrap();
+        assert_eq!(source.to_string(), "File not found");
+        assert!(err.get_source().is_none());
+        assert!(err.take_source().is_none());
+    }
+
+    #[test]
+    fn into_io_error_keeps_kind_and_inner() {
+        let err = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io::Error::new(io::ErrorKind::NotFound, "File not found")));
+        let io_err = io::Error::from(err);
+        assert_eq!(io_err.kind(), io::ErrorKind::NotFound);
+
+        let inner = io_err.into_inner().unwrap().downcast::<Error>().unwrap();
+        assert_eq!(inner.reference, "FSY-404");
+    }
+
+    #[test]
+    fn into_io_error_defaults_to_other() {
+        let io_err: io::Error = Error::new(Severity::Error, "NET-001", "Timeout").into();
+        assert_eq!(io_err.kind(), io::ErrorKind::Other);
+        assert_eq!(io_err.to_string(), "[ERR] Ref: NET-001 | Timeout");
+    }
+
+    #[cfg(not(feature = "snake-case"))]
+    #[test]
+    fn severity_serializes_pascal_case_by_default() {
+        assert_eq!(serde_json::to_string(&Severity::Critical).unwrap(), r#""Critical""#);
+        assert_eq!(serde_json::to_string(&Severity::Warning).unwrap(), r#""Warning""#);
+    }
+
+    #[cfg(feature = "snake-case")]
+    #[test]
+    fn severity_serializes_snake_case_with_feature() {
+        assert_eq!(serde_json::to_string(&Severity::Critical).unwrap(), r#""critical""#);
+        assert_eq!(serde_json::to_string(&Severity::Error).unwrap(), r#""error""#);
+        assert_eq!(serde_json::to_string(&Severity::Warning).unwrap(), r#""warning""#);
+        assert_eq!(serde_json::to_string(&Severity::Info).unwrap(), r#""info""#);
+
+        let err = Error::new(Severity::Error, "NET-001", "Timeout");
+        let json = serde_json::to_string(&err).unwrap();
+        assert!(json.starts_with(r#"{"severity":"error","reference":"NET-001""#));
+        assert_eq!(Error::from_json(&json).unwrap().severity, Severity::Error);
+    }
+
+    #[test]
+    fn retry_after_set_and_get() {
+        let err = Error::new(Severity::Warning, "API-429", "Rate limited");
+        assert_eq!(err.retry_after(), None);
+
+        let err = err.with_retry_after(Duration::from_secs(30));
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    #[cfg(not(feature = "human-durations"))]
+    fn retry_after_serializes_as_seconds() {
+        let err = Error::new(Severity::Warning, "API-429", "Rate limited")
+            .with_retry_after(Duration::from_millis(1500));
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["retry_after"], serde_json::json!(1.5));
+
+        let parsed = Error::from_json(&value.to_string()).unwrap();
+        assert_eq!(parsed.retry_after(), Some(Duration::from_millis(1500)));
+
+        let value = serde_json::to_value(Error::new(Severity::Info, "A", "a")).unwrap();
+        assert!(value.get("retry_after").is_none());
+    }
+
+    #[test]
+    fn merge_metadata_with_prefix() {
+        let inner = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .insert_metadata("filename", "data.json")
+            .insert_metadata("line", "42");
+        let outer = Error::new(Severity::Error, "CFG-001", "Cannot load config")
+            .insert_metadata("filename", "config.toml")
+            .merge_metadata_prefixed(&inner, "inner.");
+
+        assert_eq!(outer.metadata.len(), 3);
+        assert_eq!(outer.metadata["filename"], "config.toml");
+        assert_eq!(outer.metadata["inner.filename"], "data.json");
+        assert_eq!(outer.metadata["inner.line"], "42");
+    }
+
+    #[test]
+    fn or_default_below_threshold() {
+        let warn: Result<u32> = Err(Error::new(Severity::Warning, "DS-002", "Bad value"));
+        assert_eq!(warn.or_default_below(Severity::Error, 0).unwrap(), 0);
+
+        let ok: Result<u32> = Ok(5);
+        assert_eq!(ok.or_default_below(Severity::Error, 0).unwrap(), 5);
+    }
+
+    #[test]
+    fn or_default_below_propagates_at_or_above() {
+        let err: Result<u32> = Err(Error::new(Severity::Error, "DS-003", "Corrupt"));
+        let err = err.or_default_below(Severity::Error, 0).unwrap_err();
+        assert_eq!(err.reference, "DS-003");
+
+        let crit: Result<u32> = Err(Error::new(Severity::Critical, "DS-004", "Gone"));
+        assert!(crit.or_default_below(Severity::Error, 0).is_err());
+    }
+
+    #[test]
+    fn new_checked_escapes_description() {
+        let err =
+            Error::new_checked(Severity::Error, "DS-002", "line one\nline two\t\0end").unwrap();
+        assert_eq!(err.description, "line one\\nline two\\t\\u{0}end");
+        assert!(!err.to_string().contains('\n'));
+    }
+
+    #[test]
+    fn new_checked_rejects_control_in_reference() {
+        let err = Error::new_checked(Severity::Error, "DS-\u{7}002", "Bad").unwrap_err();
+        assert_eq!(err.reference, INVALID_REFERENCE);
+
+        assert!(Error::new_checked(Severity::Error, "DS-002", "Bad").is_ok());
+    }
+
+    #[test]
+    fn chain_contains_nested_reference() {
+        let db = Error::new(Severity::Error, "DB-500", "Query failed")
+            .with_source(Box::new(io::Error::other("connection lost")));
+        let api = Error::new(Severity::Error, "API-002", "Request failed").with_source(Box::new(db));
+
+        assert!(api.chain_contains_reference("DB-500"));
+        assert!(api.chain_contains_reference("API-002"));
+        assert!(!api.chain_contains_reference("NET-001"));
+    }
+
+    #[test]
+    fn from_var_error_not_present() {
+        let err = Error::from(std::env::VarError::NotPresent);
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(err.reference, "ENV");
+        assert_eq!(err.description, "Environment variable not present");
+        assert!(err.chain_find::<std::env::VarError>().is_some());
+    }
+
+    #[test]
+    fn from_var_error_not_unicode() {
+        let err = Error::from(std::env::VarError::NotUnicode("\u{fffd}".into()));
+        assert_eq!(err.reference, "ENV");
+        assert_eq!(err.description, "Environment variable is not valid unicode");
+    }
+
+    #[derive(Debug)]
+    struct Looping;
+
+    impl fmt::Display for Looping {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "looping")
+        }
+    }
+
+    impl StdError for Looping {
+        fn source(&self) -> Option<&(dyn StdError + 'static)> {
+            Some(self)
+        }
+    }
+
+    fn deep_error(levels: usize) -> Error {
+        let messages = (0..levels).map(|i| format!("level {}", i));
+        Error::new(Severity::Error, "DEEP-001", "Deep failure").with_cause_messages(messages)
+    }
+
+    #[test]
+    fn chain_depth_is_limited() {
+        let err = deep_error(DEFAULT_MAX_CHAIN_DEPTH + 4);
+
+        let mut chain = err.chain();
+        assert_eq!(chain.by_ref().count(), DEFAULT_MAX_CHAIN_DEPTH + 1);
+        assert!(chain.is_truncated());
+
+        let mut chain = err.chain_with_max_depth(2);
+        assert_eq!(chain.by_ref().count(), 3);
+        assert!(chain.is_truncated());
+
+        let shallow = deep_error(2);
+        let mut chain = shallow.chain();
+        assert_eq!(chain.by_ref().count(), 3);
+        assert!(!chain.is_truncated());
+    }
+
+    #[test]
+    fn display_and_serialization_truncate_deep_chain() {
+        let err = deep_error(DEFAULT_MAX_CHAIN_DEPTH + 4);
+
+        let out = err.to_string();
+        assert_eq!(out.matches(" | Source: level").count(), DEFAULT_MAX_CHAIN_DEPTH);
+        assert!(out.ends_with(" | Source: ...(truncated)"));
+
+        let value = serde_json::to_value(&err).unwrap();
+        let mut level = &value["source"];
+        for i in 0..DEFAULT_MAX_CHAIN_DEPTH {
+            assert_eq!(level["message"], format!("level {}", i));
+            level = &level["source"];
+        }
+        assert_eq!(level["message"], TRUNCATED_MARKER);
+        assert!(level.get("source").is_none());
+    }
+
+    #[test]
+    fn cyclic_chain_terminates() {
+        let err = Error::new(Severity::Error, "LOOP-001", "Cycle").with_source(Box::new(Looping));
+
+        let mut chain = err.chain();
+        assert_eq!(chain.by_ref().count(), 2);
+        assert!(chain.is_truncated());
+        assert_eq!(
+            err.to_string(),
+            "[ERR] Ref: LOOP-001 | Cycle | Source: looping | Source: <cycle>"
+        );
+        assert!(serde_json::to_string(&err).is_ok());
+    }
+
+    /// A newtype whose source sits at offset 0, sharing the wrapper's address.
+    #[derive(Debug)]
+    struct Wrapped(io::Error);
+
+    impl fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "wrapped io")
+        }
+    }
+
+    impl StdError for Wrapped {
+        fn source(&self) -> Option<&(dyn StdError + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn newtype_source_at_same_address_is_not_a_cycle() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout").with_source(Box::new(Wrapped(
+            io::Error::new(io::ErrorKind::TimedOut, "timed out"),
+        )));
+
+        let mut chain = err.chain();
+        assert_eq!(chain.by_ref().count(), 3);
+        assert!(!chain.is_truncated());
+        assert!(!chain.is_cycle());
+        assert!(err.chain_find::<io::Error>().is_some());
+        assert!(err.is_transient_io());
+    }
+
+    #[test]
+    fn newtype_wrapped_chain_renders_inner_source() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout").with_source(Box::new(Wrapped(
+            io::Error::new(io::ErrorKind::TimedOut, "timed out"),
+        )));
+
+        assert_eq!(
+            err.to_string(),
+            "[ERR] Ref: NET-001 | Timeout | Source: wrapped io | Source: timed out"
+        );
+        assert!(!format!("{:?}", err).contains(CYCLE_MARKER));
+
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["source"]["message"], "wrapped io");
+        assert_eq!(value["source"]["source"]["message"], "timed out");
+        assert!(!value.to_string().contains(CYCLE_MARKER));
+    }
+
+    #[test]
+    fn nested_source_round_trips() {
+        let err = deep_error(3);
+        let parsed = Error::from_json(&serde_json::to_string(&err).unwrap()).unwrap();
+        let causes: Vec<String> = parsed.chain().skip(1).map(|e| e.to_string()).collect();
+        assert_eq!(causes, ["level 0", "level 1", "level 2"]);
+    }
+
+    #[test]
+    fn metadata_serializes_in_key_order() {
+        let build = || {
+            ["zeta", "alpha", "mid", "beta", "omega", "gamma"]
+                .iter()
+                .fold(Error::new(Severity::Error, "NET-001", "Timeout"), |err, key| {
+                    err.insert_metadata(*key, key.len().to_string())
+                })
+        };
+        let first = serde_json::to_string(&build()).unwrap();
+        let second = serde_json::to_string(&build()).unwrap();
+        assert_eq!(first, second);
+        assert!(first.contains(
+            r#""metadata":{"alpha":"5","beta":"4","gamma":"5","mid":"3","omega":"5","zeta":"4"}"#
+        ));
+    }
+
+    #[test]
+    fn monotonic_order_is_strictly_increasing() {
+        let first = Error::new(Severity::Error, "NET-001", "Timeout").with_timestamp_now();
+        let second = Error::new(Severity::Error, "NET-001", "Timeout").with_timestamp_now();
+        assert!(first.monotonic_order().unwrap() < second.monotonic_order().unwrap());
+        assert!(first.timestamp().is_some());
+
+        let untimed = Error::new(Severity::Error, "NET-001", "Timeout");
+        assert_eq!(untimed.monotonic_order(), None);
+        assert_eq!(untimed.timestamp(), None);
+    }
+
+    #[test]
+    fn timestamp_serializes_as_unix_millis() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout").with_timestamp_now();
+        let value = serde_json::to_value(&err).unwrap();
+        assert!(value["timestamp"].is_u64());
+        assert!(value.get("monotonic_order").is_none());
+
+        let parsed = Error::from_json(&value.to_string()).unwrap();
+        let millis = |time: SystemTime| time.duration_since(UNIX_EPOCH).unwrap().as_millis();
+        assert_eq!(millis(parsed.timestamp().unwrap()), millis(err.timestamp().unwrap()));
+        assert_eq!(parsed.monotonic_order(), None);
+    }
+
+    #[test]
+    fn from_poisoned_mutex() {
+        let lock = std::sync::Arc::new(std::sync::Mutex::new(0));
+        let poisoner = std::sync::Arc::clone(&lock);
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poisoning the lock");
+        })
+        .join();
+
+        let err = Error::from_poison(lock.lock().unwrap_err());
+        assert_eq!(err.severity, Severity::Critical);
+        assert_eq!(err.reference, "LOCK-POISON");
+        assert!(err.description.starts_with("Lock poisoned by a panicking thread: "));
+        assert!(err.get_source().is_none());
+    }
+
+    #[test]
+    fn severity_constructors() {
+        let cases = [
+            (Error::critical("SYS-001", "Out of memory"), Severity::Critical),
+            (Error::error("SYS-001", "Out of memory"), Severity::Error),
+            (Error::warning("SYS-001", "Out of memory"), Severity::Warning),
+            (Error::info("SYS-001", "Out of memory"), Severity::Info),
+        ];
+        for (err, severity) in cases {
+            assert_eq!(err.severity, severity);
+            assert_eq!(err.reference, "SYS-001");
+            assert_eq!(err.description, "Out of memory");
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json;
-    use std::io;
+    #[test]
+    fn attach_transforms_error() {
+        let failed: Result<()> = Err(Error::new(Severity::Error, "NET-001", "Timeout"));
+        let err = failed
+            .attach(|e| {
+                let category = if e.reference.starts_with("NET") { "network" } else { "other" };
+                e.insert_metadata("category", category)
+            })
+            .unwrap_err();
+        assert_eq!(err.metadata["category"], "network");
+
+        let ok: Result<u8> = Ok(1);
+        assert_eq!(ok.attach(|_| panic!("not called on Ok")).unwrap(), 1);
+    }
 
     #[test]
-    fn create_basic_error() {
-        let err = Error::new(Severity::Error, "NET-001", "Timeout");
-        assert_eq!(err.severity, Severity::Error);
-        assert_eq!(err.reference, "NET-001");
-        assert_eq!(err.description, "Timeout");
-        assert!(err.source.is_none());
+    fn recover_with_fallback() {
+        let failed: Result<u32> = Err(Error::new(Severity::Warning, "CFG-404", "Missing key"));
+        let value = failed.recover(|e| (e.reference == "CFG-404").then_some(8080));
+        assert_eq!(value.unwrap(), 8080);
     }
 
     #[test]
-    fn attach_source_error() {
+    fn recover_propagates_and_passes_ok() {
+        let failed: Result<u32> = Err(Error::new(Severity::Error, "CFG-500", "Corrupt file"));
+        let err = failed.recover(|_| None).unwrap_err();
+        assert_eq!(err.reference, "CFG-500");
+
+        let ok: Result<u32> = Ok(1);
+        assert_eq!(ok.recover(|_| panic!("not called on Ok")).unwrap(), 1);
+    }
+
+    #[test]
+    fn map_mcp_description_reuses_the_owned_buffer() {
+        let mut description = String::with_capacity(64);
+        description.push_str("Failed to read config");
+        let ptr = description.as_ptr();
+        let e = Err::<(), _>(io::Error::other("disk gone"))
+            .map_mcp_err("FSY-READ", description)
+            .unwrap_err();
+
+        assert_eq!(e.description, "Failed to read config: disk gone");
+        assert_eq!(e.description.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn context_breadcrumbs_in_display() {
+        let err = Error::new(Severity::Error, "CFG-002", "Invalid port")
+            .push_context("while loading config")
+            .push_context("while parsing section [db]")
+            .with_source(Box::new(io::Error::other("not a number")));
+        assert_eq!(
+            err.to_string(),
+            "[ERR] Ref: CFG-002 | Invalid port | Context: while loading config \
+             > while parsing section [db] | Source: not a number"
+        );
+        assert_eq!(err.context().len(), 2);
+    }
+
+    #[test]
+    fn context_skipped_when_empty_and_roundtrips() {
+        let err = Error::new(Severity::Error, "CFG-002", "Invalid port");
+        assert!(!serde_json::to_string(&err).unwrap().contains("context"));
+
+        let err = err.push_context("while loading config");
+        let back: Error = serde_json::from_str(&serde_json::to_string(&err).unwrap()).unwrap();
+        assert_eq!(back.context(), ["while loading config"]);
+    }
+
+    #[test]
+    fn into_string_matches_display() {
+        let err = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io::Error::other("denied")));
+        let display = err.to_string();
+
+        assert_eq!(String::from(&err), display);
+        let s: String = err.into();
+        assert_eq!(s, display);
+    }
+
+    #[test]
+    fn dedup_chain_collapses_consecutive_repeats() {
+        let io_err = io::Error::other("connection refused");
+        let inner = Error::new(Severity::Error, "NET-001", "connection refused")
+            .with_source(Box::new(io_err));
+        let middle = Error::new(Severity::Error, "NET-002", "connection refused")
+            .with_source(Box::new(inner));
+        let err = Error::new(Severity::Error, "API-001", "Request failed")
+            .with_source(Box::new(middle));
+
+        assert_eq!(err.dedup_chain(), ["Request failed", "connection refused"]);
+        assert_eq!(
+            err.to_deduped_string(),
+            "[ERR] Ref: API-001 | Request failed | Source: connection refused"
+        );
+    }
+
+    #[test]
+    fn dedup_chain_keeps_non_consecutive_repeats() {
+        let err = Error::new(Severity::Error, "A", "retry")
+            .with_cause_messages(["timeout".to_string(), "retry".to_string()]);
+        assert_eq!(err.dedup_chain(), ["retry", "timeout", "retry"]);
+    }
+
+    #[test]
+    fn serialized_source_carries_known_type_names() {
         let io_err = io::Error::new(io::ErrorKind::NotFound, "File not found");
-        let mcp_err = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+        let err = Error::new(Severity::Error, "CFG-001", "Cannot load config")
+            .with_cause_messages(["opaque".to_string()]);
+        let json = serde_json::to_value(&err).unwrap();
+        assert!(json["source"].get("type").is_none());
+
+        let inner = Error::new(Severity::Error, "FSY-404", "Cannot read file")
             .with_source(Box::new(io_err));
+        let err = Error::new(Severity::Error, "CFG-001", "Cannot load config")
+            .with_source(Box::new(inner));
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["source"]["type"], "mcp_error::Error");
+        assert_eq!(json["source"]["source"]["type"], "std::io::Error");
+        assert_eq!(json["source"]["source"]["message"], "File not found");
 
-        // The Display should include the source:
-        let out = format!("{}", mcp_err);
-        assert!(out.contains("Source: File not found"));
+        // The names survive a roundtrip through `MessageError`.
+        let back = Error::from_json(&json.to_string()).unwrap();
+        assert_eq!(serde_json::to_value(&back).unwrap(), json);
+    }
 
-        // The source() method should return Some
-        assert!(mcp_err.source().is_some());
+    #[test]
+    fn rich_error_is_not_low_quality() {
+        let err = Error::new(Severity::Error, "CFG-001", "Error")
+            .insert_metadata("filename", "config.toml");
+        assert!(!err.is_low_quality());
+
+        let err = Error::new(Severity::Error, "NET-001", "Unknown error")
+            .with_source(Box::new(io::Error::other("connection refused")));
+        assert!(!err.is_low_quality());
+
+        let err = Error::new(Severity::Error, "CFG-001", "Port must be between 1 and 65535");
+        assert!(!err.is_low_quality());
     }
 
     #[test]
-    fn insert_metadata() {
-        let mut err = Error::new(Severity::Warning, "DS-002", "Data parse incomplete");
-        err = err.insert_metadata("filename", "data.json");
-        err = err.insert_metadata("line", "42");
+    fn bare_generic_error_is_low_quality() {
+        assert!(Error::new(Severity::Error, "E", "Something went wrong!").is_low_quality());
+        assert!(Error::new(Severity::Error, "E", "  An unexpected error occurred.")
+            .is_low_quality());
+        assert!(Error::new(Severity::Error, "E", "bad input").is_low_quality());
+    }
 
-        assert_eq!(err.metadata["filename"], "data.json");
-        assert_eq!(err.metadata["line"], "42");
+    #[test]
+    fn remove_metadata_drops_only_that_key() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout")
+            .insert_metadata("host", "db")
+            .insert_metadata("internal.trace", "abc")
+            .remove_metadata("internal.trace")
+            .remove_metadata("missing");
+        assert_eq!(err.metadata, metadata! { "host" => "db" });
     }
 
     #[test]
-    fn serialize_error() {
+    fn retain_metadata_by_predicate() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout")
+            .insert_metadata("host", "db")
+            .insert_metadata("internal.trace", "abc")
+            .insert_metadata("port", "")
+            .retain_metadata(|key, value| !key.starts_with("internal.") && !value.is_empty());
+        assert_eq!(err.metadata, metadata! { "host" => "db" });
+    }
+
+    #[test]
+    fn with_source_promoting_raises_to_critical_source() {
+        let inner = Error::critical("DS-001", "Index corrupted");
+        let err = Error::warning("API-001", "Search degraded")
+            .with_source_promoting(Box::new(inner));
+        assert_eq!(err.severity, Severity::Critical);
+        assert_eq!(err.get_source().unwrap().to_string(), "[CRIT] Ref: DS-001 | Index corrupted");
+    }
+
+    #[test]
+    fn with_source_promoting_never_lowers() {
+        let inner = Error::info("DS-002", "Cache miss");
+        let err = Error::error("API-001", "Search failed").with_source_promoting(Box::new(inner));
+        assert_eq!(err.severity, Severity::Error);
+
+        let err = Error::warning("API-001", "Search degraded")
+            .with_source_promoting(Box::new(io::Error::other("timeout")));
+        assert_eq!(err.severity, Severity::Warning);
+    }
+
+    #[test]
+    fn log_continue_branches() {
+        use std::cell::RefCell;
+
+        let logged = RefCell::new(Vec::new());
+        let log = |e: &Error| logged.borrow_mut().push(e.reference.clone());
+
+        let ok: Result<u32> = Ok(7);
+        assert_eq!(ok.log_continue(Severity::Error, log).unwrap(), Some(7));
+
+        let minor: Result<u32> = Err(Error::warning("DS-001", "Skipped row"));
+        assert_eq!(minor.log_continue(Severity::Error, log).unwrap(), None);
+
+        let fatal: Result<u32> = Err(Error::error("DS-002", "Corrupt file"));
+        assert_eq!(fatal.log_continue(Severity::Error, log).unwrap_err().reference, "DS-002");
+
+        assert_eq!(*logged.borrow(), ["DS-001"]);
+    }
+
+    #[test]
+    fn log_continue_in_a_loop() {
+        fn process(items: Vec<Result<u32>>) -> Result<(u32, usize)> {
+            let skipped = std::cell::Cell::new(0);
+            let mut sum = 0;
+            for item in items {
+                let log = |_: &Error| skipped.set(skipped.get() + 1);
+                let Some(value) = item.log_continue(Severity::Error, log)? else {
+                    continue;
+                };
+                sum += value;
+            }
+            Ok((sum, skipped.get()))
+        }
+
+        let items = vec![Ok(1), Err(Error::info("DS-001", "Empty row")), Ok(2)];
+        assert_eq!(process(items).unwrap(), (3, 1));
+
+        let items = vec![Ok(1), Err(Error::critical("DS-002", "Disk gone")), Ok(2)];
+        assert_eq!(process(items).unwrap_err().reference, "DS-002");
+    }
+
+    #[test]
+    fn warn_and_default_branches() {
+        use std::cell::RefCell;
+
+        let logged = RefCell::new(Vec::new());
+        let log = |e: &Error| logged.borrow_mut().push((e.reference.clone(), e.severity));
+
+        let ok: Result<u32> = Ok(7);
+        assert_eq!(ok.warn_and_default(0, log), 7);
+        assert!(logged.borrow().is_empty());
+
+        let failed: Result<u32> = Err(Error::critical("CFG-001", "Cannot load config"));
+        assert_eq!(failed.warn_and_default(0, log), 0);
+
+        let minor: Result<u32> = Err(Error::info("CFG-002", "Using defaults"));
+        assert_eq!(minor.warn_and_default(3, log), 3);
+
+        assert_eq!(
+            *logged.borrow(),
+            [
+                ("CFG-001".to_string(), Severity::Critical),
+                ("CFG-002".to_string(), Severity::Info)
+            ]
+        );
+    }
+
+    #[test]
+    fn severity_arithmetic() {
+        assert_eq!(Severity::Info + 2, Severity::Error);
+        assert_eq!(Severity::Critical - 1, Severity::Error);
+        assert_eq!(Severity::Warning + -1, Severity::Info);
+        assert_eq!(Severity::Warning - -2, Severity::Critical);
+        assert_eq!(Severity::Error + 0, Severity::Error);
+    }
+
+    #[test]
+    fn severity_arithmetic_saturates() {
+        assert_eq!(Severity::Error + 5, Severity::Critical);
+        assert_eq!(Severity::Critical + i32::MAX, Severity::Critical);
+        assert_eq!(Severity::Warning - 3, Severity::Info);
+        assert_eq!(Severity::Info - i32::MIN, Severity::Critical);
+        assert_eq!(Severity::Info.deescalate(), Severity::Info);
+        assert_eq!(Severity::Critical.escalate(), Severity::Critical);
+        assert_eq!(Severity::Warning.escalate(), Severity::Error);
+    }
+
+    #[test]
+    fn metadata_introspection() {
         let err = Error::new(Severity::Error, "NET-001", "Timeout");
-        let serialized = serde_json::to_string(&err).unwrap();
-        let expected = r#"{"severity":"Error","reference":"NET-001","description":"Timeout","metadata":{},"source":null}"#;
-        assert_eq!(serialized, expected);
+        assert!(!err.has_metadata());
+        assert_eq!(err.metadata_len(), 0);
+
+        let err = err.insert_metadata("port", "5432").insert_metadata("host", "db");
+        assert!(err.has_metadata());
+        assert_eq!(err.metadata_len(), 2);
+        let mut keys: Vec<&str> = err.metadata_keys().collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["host", "port"]);
     }
 
     #[test]
-    fn serialize_error_with_source() {
-        let io_err = io::Error::new(io::ErrorKind::NotFound, "File not found");
-        let ephais_err = Error::new(Severity::Error, "FSY-404", "Cannot read file")
-            .with_source(Box::new(io_err));
+    fn severity_from_str_is_strict() {
+        assert_eq!("Critical".parse::<Severity>(), Ok(Severity::Critical));
+        assert_eq!("warn".parse::<Severity>(), Ok(Severity::Warning));
+        assert_eq!("ERR".parse::<Severity>(), Ok(Severity::Error));
+        assert_eq!("info".parse::<Severity>(), Ok(Severity::Info));
+        let err = "fatal".parse::<Severity>().unwrap_err();
+        assert_eq!(err.to_string(), "unknown severity: \"fatal\"");
+    }
 
-        let serialized = serde_json::to_string(&ephais_err).unwrap();
-        let expected = r#"{"severity":"Error","reference":"FSY-404","description":"Cannot read file","metadata":{},"source":{"message":"File not found"}}"#;
-        assert_eq!(serialized, expected);
+    #[test]
+    fn severity_parse_loose_aliases() {
+        assert_eq!(Severity::parse_loose("fatal"), Severity::Critical);
+        assert_eq!(Severity::parse_loose("EMERG"), Severity::Critical);
+        assert_eq!(Severity::parse_loose("err"), Severity::Error);
+        assert_eq!(Severity::parse_loose(" Warning "), Severity::Warning);
+        assert_eq!(Severity::parse_loose("information"), Severity::Info);
+        assert_eq!(Severity::parse_loose("debug"), Severity::Info);
+    }
+
+    #[test]
+    fn severity_parse_loose_defaults_to_info() {
+        assert_eq!(Severity::parse_loose("bogus"), Severity::Info);
+        assert_eq!(Severity::parse_loose(""), Severity::Info);
+    }
+
+    #[test]
+    fn docs_url_derived_from_base() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout");
+        let expected = "https://docs.example.com/errors/NET-001";
+        assert_eq!(err.docs_url("https://docs.example.com/errors"), expected);
+        assert_eq!(err.docs_url("https://docs.example.com/errors/"), expected);
+        assert!(!serde_json::to_string(&err).unwrap().contains("docs_url"));
+    }
+
+    #[test]
+    fn docs_url_explicit_setter_wins_and_serializes() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout")
+            .with_docs_url("https://wiki.example.com/timeouts");
+        assert_eq!(
+            err.docs_url("https://docs.example.com/errors"),
+            "https://wiki.example.com/timeouts"
+        );
+
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["docs_url"], "https://wiki.example.com/timeouts");
+        let back = Error::from_json(&json.to_string()).unwrap();
+        assert_eq!(back.docs_url(""), "https://wiki.example.com/timeouts");
+    }
+
+    #[test]
+    fn severity_filter_predicates() {
+        let errors = [
+            Error::info("A", "a"),
+            Error::critical("B", "b"),
+            Error::warning("C", "c"),
+            Error::error("D", "d"),
+            Error::warning("E", "e"),
+        ];
+        let refs = |pred: &dyn Fn(&&Error) -> bool| -> Vec<&str> {
+            errors.iter().filter(pred).map(|e| e.reference.as_str()).collect()
+        };
+
+        assert_eq!(refs(&Severity::at_least_filter(Severity::Error)), ["B", "D"]);
+        assert_eq!(refs(&Severity::at_least_filter(Severity::Info)).len(), 5);
+        assert_eq!(refs(&Severity::exactly(Severity::Warning)), ["C", "E"]);
+
+        let count = errors.iter().filter(Severity::at_least_filter(Severity::Warning)).count();
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn cyclic_chain_renders_cycle_marker() {
+        let err = Error::new(Severity::Error, "LOOP-001", "Cycle").with_source(Box::new(Looping));
+
+        let mut chain = err.chain();
+        chain.by_ref().for_each(drop);
+        assert!(chain.is_cycle());
+        assert_eq!(chain.end_marker(), Some(CYCLE_MARKER));
+
+        assert!(format!("{:?}", err).ends_with("source: [Looping, <cycle>] }"));
+        assert!(format!("{:#?}", err).contains("<cycle>"));
+
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["source"]["message"], "looping");
+        assert_eq!(value["source"]["source"]["message"], CYCLE_MARKER);
+    }
+
+    #[test]
+    fn depth_limit_is_not_a_cycle() {
+        let err = deep_error(DEFAULT_MAX_CHAIN_DEPTH + 1);
+        let mut chain = err.chain();
+        chain.by_ref().for_each(drop);
+        assert!(chain.is_truncated());
+        assert!(!chain.is_cycle());
+        assert_eq!(chain.end_marker(), Some(TRUNCATED_MARKER));
+    }
+
+    #[test]
+    fn debug_lists_source_chain() {
+        let inner = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io::Error::new(io::ErrorKind::NotFound, "File not found")));
+        let err = Error::new(Severity::Error, "CFG-001", "Cannot load config")
+            .with_source(Box::new(inner));
+        let debug = format!("{:?}", err);
+        assert!(debug.starts_with("Error { severity: Error, reference: \"CFG-001\""));
+        assert!(debug.contains("source: [Error { severity: Error, reference: \"FSY-404\""));
+        assert!(debug.contains("kind: NotFound"));
+        assert!(format!("{:?}", Error::info("A", "a")).ends_with("source: None }"));
+    }
+
+    #[test]
+    fn severity_from_http_status() {
+        let derived = |status| {
+            Error::info("API-001", "Request failed")
+                .with_http_status(status)
+                .with_severity_from_status()
+                .severity
+        };
+        assert_eq!(derived(500), Severity::Critical);
+        assert_eq!(derived(503), Severity::Error);
+        assert_eq!(derived(429), Severity::Warning);
+        assert_eq!(derived(404), Severity::Warning);
+        assert_eq!(derived(302), Severity::Info);
+        assert_eq!(derived(200), Severity::Info);
+    }
+
+    #[test]
+    fn severity_from_status_without_status_is_a_no_op() {
+        let err = Error::critical("API-001", "Request failed").with_severity_from_status();
+        assert_eq!(err.severity, Severity::Critical);
+        assert_eq!(err.http_status(), None);
+
+        let err = Error::warning("API-001", "Request failed")
+            .with_http_status(999)
+            .with_severity_from_status();
+        assert_eq!(err.severity, Severity::Warning);
+        assert_eq!(serde_json::to_value(&err).unwrap()["http_status"], 999);
+    }
+
+    #[test]
+    fn compose_chains_and_takes_max_severity() {
+        let primary = Error::warning("SYNC-001", "Upload failed");
+        let secondary = Error::critical("SYNC-002", "Rollback failed")
+            .with_source(Box::new(io::Error::other("disk full")));
+        let err = Error::compose(primary, secondary);
+
+        assert_eq!(err.severity, Severity::Critical);
+        assert_eq!(err.reference, "SYNC-001");
+        assert!(err.chain_contains_reference("SYNC-002"));
+        assert_eq!(
+            err.to_string(),
+            "[CRIT] Ref: SYNC-001 | Upload failed | Source: Rollback failed | Source: disk full"
+        );
+
+        let err = Error::compose(Error::error("A", "a"), Error::info("B", "b"));
+        assert_eq!(err.severity, Severity::Error);
+    }
+
+    #[test]
+    fn with_metadata_constructor() {
+        let err = Error::with_metadata(
+            Severity::Warning,
+            "NET-002",
+            "Slow response",
+            metadata! { "host" => "db" },
+        )
+        .with_source(Box::new(io::Error::other("latency")));
+
+        assert_eq!(err.severity, Severity::Warning);
+        assert_eq!(err.reference, "NET-002");
+        assert_eq!(err.metadata["host"], "db");
+        assert_eq!(err.to_string(), "[WARN] Ref: NET-002 | Slow response | Source: latency");
+    }
+
+    #[test]
+    fn equality_uses_identity_fields() {
+        let err = || Error::error("NET-001", "Timeout").insert_metadata("host", "db");
+        let decorated = err()
+            .with_source(Box::new(io::Error::other("x")))
+            .with_timestamp_now();
+        assert_eq!(err(), decorated);
+        assert_ne!(err(), err().insert_metadata("port", "5432"));
+        assert_ne!(err(), Error::new(Severity::Warning, "NET-001", "Timeout"));
+    }
+
+    #[test]
+    fn truncate_metadata_values_is_char_safe() {
+        let err = Error::new(Severity::Error, "API-001", "Request failed")
+            .insert_metadata("body", "héllo wörld ✓✓✓")
+            .insert_metadata("short", "ok")
+            .truncate_metadata_values(8);
+        assert_eq!(err.metadata["body"], "héllo w…");
+        assert_eq!(err.metadata["body"].chars().count(), 8);
+        assert_eq!(err.metadata["short"], "ok");
+
+        let err = err.insert_metadata("exact", "12345678").truncate_metadata_values(8);
+        assert_eq!(err.metadata["exact"], "12345678");
+
+        let err = err.truncate_metadata_values(0);
+        assert_eq!(err.metadata["short"], "");
+    }
+
+    #[test]
+    fn classify_io_error_by_kind() {
+        fn classify_io(err: &io::Error) -> (Severity, String) {
+            match err.kind() {
+                io::ErrorKind::NotFound => (Severity::Warning, "FSY-404".to_string()),
+                io::ErrorKind::PermissionDenied => (Severity::Error, "FSY-403".to_string()),
+                _ => (Severity::Critical, "FSY-500".to_string()),
+            }
+        }
+
+        let err = Error::classify(io::Error::from(io::ErrorKind::NotFound), classify_io);
+        assert_eq!((err.severity, err.reference.as_str()), (Severity::Warning, "FSY-404"));
+        assert!(err.chain_find::<io::Error>().is_some());
+
+        let denied = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+        let err = Error::classify(denied, classify_io);
+        assert_eq!((err.severity, err.reference.as_str()), (Severity::Error, "FSY-403"));
+        assert_eq!(err.description, "denied");
+
+        let err = Error::classify(io::Error::other("boom"), classify_io);
+        assert_eq!(err.reference, "FSY-500");
+    }
+
+    #[test]
+    fn severity_compares_with_names_and_abbreviations() {
+        for (severity, name, abbreviation) in [
+            (Severity::Critical, "Critical", "CRIT"),
+            (Severity::Error, "Error", "ERR"),
+            (Severity::Warning, "Warning", "WARN"),
+            (Severity::Info, "Info", "INFO"),
+        ] {
+            assert_eq!(severity.name(), name);
+            assert_eq!(severity.abbreviation(), abbreviation);
+            assert!(severity == name);
+            assert!(severity == abbreviation);
+            assert!(severity == *abbreviation.to_lowercase());
+        }
+        assert!(Severity::Error != "WARN");
+        assert!(Severity::Error != "fatal");
+    }
+
+    #[test]
+    fn attach_result_ok_keeps_error_untouched() {
+        let err = Error::error("CFG-001", "Cannot load config");
+        let (err, value) = err.attach_result(Ok::<_, io::Error>(42));
+        assert_eq!(value, Some(42));
+        assert!(err.get_source().is_none());
+    }
+
+    #[test]
+    fn attach_result_err_becomes_source() {
+        let err = Error::error("CFG-001", "Cannot load config");
+        let (err, value) = err.attach_result(Err::<u32, _>(io::Error::other("denied")));
+        assert_eq!(value, None);
+        assert_eq!(err.to_string(), "[ERR] Ref: CFG-001 | Cannot load config | Source: denied");
+    }
+
+    #[test]
+    #[cfg(feature = "human-durations")]
+    fn retry_after_serializes_ms_and_human_form() {
+        let retry_after = |duration| {
+            let err = Error::new(Severity::Warning, "API-429", "Rate limited")
+                .with_retry_after(duration);
+            serde_json::to_value(&err).unwrap()["retry_after"].clone()
+        };
+        assert_eq!(
+            retry_after(Duration::from_millis(1500)),
+            serde_json::json!({"ms": 1500, "human": "1.5s"})
+        );
+        assert_eq!(retry_after(Duration::from_millis(250))["human"], "250ms");
+        assert_eq!(retry_after(Duration::from_secs(2))["human"], "2s");
+        assert_eq!(retry_after(Duration::from_secs(125))["human"], "2m 5s");
+        assert_eq!(retry_after(Duration::from_secs(7200))["human"], "2h");
+    }
+
+    #[test]
+    fn retry_after_accepts_both_forms() {
+        let parse = |retry_after: &str| {
+            let mut value = serde_json::to_value(Error::info("A", "a")).unwrap();
+            value["retry_after"] = serde_json::from_str(retry_after).unwrap();
+            Error::from_json(&value.to_string()).unwrap().retry_after()
+        };
+        assert_eq!(parse("1.5"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse(r#"{"ms":1500,"human":"1.5s"}"#), Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn into_parts_from_parts_round_trip() {
+        let err = Error::new(Severity::Warning, "FSY-404", "Cannot read file")
+            .insert_metadata("filename", "data.json")
+            .with_source(Box::new(io::Error::new(io::ErrorKind::NotFound, "File not found")));
+        let display = err.to_string();
+
+        let (severity, reference, description, mut metadata, source) = err.into_parts();
+        assert_eq!(severity, Severity::Warning);
+        assert_eq!(reference, "FSY-404");
+        assert_eq!(description, "Cannot read file");
+        assert_eq!(source.as_ref().unwrap().to_string(), "File not found");
+        metadata.insert("line".to_string(), "42".to_string());
+
+        let back = Error::from_parts((severity, reference, description, metadata, source));
+        assert_eq!(back.to_string(), display);
+        assert_eq!(back.metadata["line"], "42");
+        assert!(back.chain_find::<io::Error>().is_some());
+    }
+
+    #[test]
+    fn map_mcp_merges_existing_error() {
+        let inner: Result<()> = Err(Error::warning("FSY-404", "Cannot read file")
+            .insert_metadata("filename", "config.toml")
+            .with_source(Box::new(io::Error::new(io::ErrorKind::NotFound, "File not found"))));
+        let err = inner.map_mcp_err("CFG-001", "Cannot load config").unwrap_err();
+
+        assert_eq!(err.reference, "CFG-001");
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(err.description, "Cannot load config: Cannot read file");
+        assert_eq!(err.metadata["filename"], "config.toml");
+        assert_eq!(err.metadata[CAUSE_REFERENCE_KEY], "FSY-404");
+        // Outer error plus the io::Error: no extra wrapping level.
+        assert_eq!(err.chain().count(), 2);
+        assert_eq!(
+            err.to_string(),
+            "[ERR] Ref: CFG-001 | Cannot load config: Cannot read file | Source: File not found"
+        );
+    }
+
+    #[test]
+    fn map_mcp_merge_keeps_higher_severity_and_root_reference() {
+        let inner: Result<()> = Err(Error::critical("DS-001", "Index corrupted"));
+        let err = inner
+            .map_mcp_inf("API-001", "Search failed")
+            .map_mcp_err("APP-001", "Request failed")
+            .unwrap_err();
+        assert_eq!(err.severity, Severity::Critical);
+        assert_eq!(err.reference, "APP-001");
+        assert_eq!(err.metadata[CAUSE_REFERENCE_KEY], "DS-001");
+        assert_eq!(err.chain().count(), 1);
+
+        let boxed: std::result::Result<(), Box<dyn StdError + Send + Sync>> =
+            Err(Box::new(Error::error("DS-002", "Corrupt")));
+        let err = EphBoxedErrorExt::map_mcp_crit(boxed, "API-002", "Failed").unwrap_err();
+        assert_eq!(err.chain().count(), 1);
+        assert_eq!(err.severity, Severity::Critical);
+    }
+
+    #[test]
+    fn explicit_fingerprint_wins_and_serializes() {
+        let err = Error::error("NET-001", "Timeout").with_fingerprint("net-timeouts");
+        assert_eq!(err.computed_fingerprint(), "net-timeouts");
+        assert_eq!(serde_json::to_value(&err).unwrap()["fingerprint"], "net-timeouts");
+
+        let plain = Error::error("NET-001", "Timeout");
+        assert!(serde_json::to_value(&plain).unwrap().get("fingerprint").is_none());
+    }
+
+    #[test]
+    fn computed_fingerprint_is_stable() {
+        let err = || {
+            Error::error("NET-001", "Timeout")
+                .with_source(Box::new(io::Error::other("connection reset")))
+        };
+        // Pinned value: the hash must not change between runs or releases.
+        assert_eq!(err().computed_fingerprint(), "ee25465d87d017ac");
+        assert_eq!(err().computed_fingerprint(), err().computed_fingerprint());
+
+        let noisy = Error::error(" net-001 ", "Timeout ")
+            .insert_metadata("host", "db")
+            .with_source(Box::new(io::Error::other("connection reset")));
+        assert_eq!(noisy.computed_fingerprint(), err().computed_fingerprint());
+
+        let other_cause = Error::error("NET-001", "Timeout")
+            .with_source(Box::new(io::Error::other("refused")));
+        assert_ne!(other_cause.computed_fingerprint(), err().computed_fingerprint());
+    }
+
+    #[test]
+    fn severity_forms_in_custom_serialize_with() {
+        type Out<S> = std::result::Result<<S as Serializer>::Ok, <S as Serializer>::Error>;
+
+        fn as_abbreviation<S: Serializer>(severity: &Severity, s: S) -> Out<S> {
+            s.serialize_str(severity.abbreviation())
+        }
+        fn as_level<S: Serializer>(severity: &Severity, s: S) -> Out<S> {
+            s.serialize_u8(severity.level())
+        }
+        fn as_symbol<S: Serializer>(severity: &Severity, s: S) -> Out<S> {
+            s.serialize_str(severity.symbol())
+        }
+        fn as_name<S: Serializer>(severity: &Severity, s: S) -> Out<S> {
+            s.serialize_str(severity.name())
+        }
+
+        #[derive(Serialize)]
+        struct Event {
+            #[serde(serialize_with = "as_abbreviation")]
+            short: Severity,
+            #[serde(serialize_with = "as_level")]
+            level: Severity,
+            #[serde(serialize_with = "as_symbol")]
+            symbol: Severity,
+            #[serde(serialize_with = "as_name")]
+            name: Severity,
+        }
+
+        let severity = Severity::Warning;
+        let json = serde_json::to_value(Event {
+            short: severity,
+            level: severity,
+            symbol: severity,
+            name: severity,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"short": "WARN", "level": 1, "symbol": "⚠️", "name": "Warning"})
+        );
+        assert_eq!(Severity::from_level(1), Some(severity));
+        assert_eq!(Severity::from_level(4), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn from_exit_status_records_code() {
+        use std::os::unix::process::ExitStatusExt;
+
+        // Raw wait status: exit code in the high byte.
+        let status = std::process::ExitStatus::from_raw(3 << 8);
+        let err = Error::from_exit_status(status, "cargo build");
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(err.reference, "PROC");
+        assert_eq!(err.metadata["command"], "cargo build");
+        assert_eq!(err.metadata["exit_code"], "3");
+        assert_eq!(err.description, "Command 'cargo build' failed: exit status: 3");
+
+        // Killed by SIGKILL.
+        let err = Error::from_exit_status(std::process::ExitStatus::from_raw(9), "sleep 60");
+        assert_eq!(err.metadata["signal"], "9");
+        assert!(!err.metadata.contains_key("exit_code"));
+    }
+
+    #[test]
+    fn attachments_round_trip_by_type() {
+        #[derive(Debug, PartialEq)]
+        struct Request {
+            id: u32,
+            path: String,
+        }
+
+        let err = Error::new(Severity::Error, "API-400", "Bad request").with_attachment(Request {
+            id: 7,
+            path: "/tools".to_string(),
+        });
+        assert_eq!(
+            err.attachment::<Request>(),
+            Some(&Request {
+                id: 7,
+                path: "/tools".to_string()
+            })
+        );
+        assert_eq!(err.attachment::<u32>(), None);
+
+        let err = err.with_attachment(1u32).with_attachment(2u32);
+        assert_eq!(err.attachment::<u32>(), Some(&2));
+
+        let json = serde_json::to_value(&err).unwrap();
+        assert!(json.get("attachments").is_none());
+    }
+
+    #[test]
+    fn chain_references_skip_foreign_sources() {
+        let c = Error::new(Severity::Error, "C", "Cannot read file")
+            .with_source(Box::new(io::Error::other("File not found")));
+        let b = Error::new(Severity::Error, "B", "Cannot load config").with_source(Box::new(c));
+        let a = Error::new(Severity::Critical, "A", "Startup failed").with_source(Box::new(b));
+        assert_eq!(a.chain_references(), ["A", "B", "C"]);
+
+        let leaf = Error::new(Severity::Info, "LEAF", "Done");
+        assert_eq!(leaf.chain_references(), ["LEAF"]);
+    }
+
+    #[test]
+    fn extend_adds_metadata_pairs() {
+        let mut err = Error::new(Severity::Warning, "DS-002", "Data parse incomplete")
+            .insert_metadata("line", "1");
+        err.extend(vec![
+            ("filename".to_string(), "data.json".to_string()),
+            ("line".to_string(), "42".to_string()),
+        ]);
+        err.extend(["column", "row"].map(|key| (key.to_string(), "7".to_string())));
+
+        assert_eq!(err.metadata_len(), 4);
+        assert_eq!(err.metadata["filename"], "data.json");
+        assert_eq!(err.metadata["line"], "42");
+        assert_eq!(err.metadata["column"], "7");
+        assert_eq!(err.metadata["row"], "7");
+    }
+
+    #[test]
+    fn display_keeps_context_of_nested_error() {
+        let inner = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .push_context("open config")
+            .push_context("read")
+            .with_source(Box::new(io::Error::other("File not found")));
+        let err = Error::new(Severity::Critical, "CFG-001", "Cannot load config")
+            .push_context("startup")
+            .with_source(Box::new(inner));
+
+        assert_eq!(
+            err.to_string(),
+            "[CRIT] Ref: CFG-001 | Cannot load config | Context: startup \
+             | Source: Cannot read file | Context: open config > read | Source: File not found"
+        );
     }
 }
@@ -0,0 +1,181 @@
+//! `log` integration (`log` feature): `Severity` / `log::Level` conversions
+//! and routed emission.
+
+use crate::{Error, Severity};
+
+impl From<Severity> for log::Level {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Critical | Severity::Error => log::Level::Error,
+            Severity::Warning => log::Level::Warn,
+            Severity::Info => log::Level::Info,
+        }
+    }
+}
+
+/// `Debug` and `Trace` have no dedicated severity and map to `Info`.
+impl From<log::Level> for Severity {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => Severity::Error,
+            log::Level::Warn => Severity::Warning,
+            log::Level::Info | log::Level::Debug | log::Level::Trace => Severity::Info,
+        }
+    }
+}
+
+impl Error {
+    /// Logs the error's `Display` form at its severity's level, unless it is
+    /// below `MCP_MIN_SEVERITY` (see [`crate::MIN_SEVERITY_ENV_VAR`]).
+    pub fn emit(&self) {
+        if crate::passes_env_filter(self.severity) {
+            log::log!(log::Level::from(self.severity), "{}", self);
+        }
+    }
+
+    /// Logs the error's `Display` form at its severity's level on
+    /// `default_target`; `Critical` errors are also logged on `alert_target`
+    /// so an alert channel can pick them up.
+    ///
+    /// Like `emit`, errors below `MCP_MIN_SEVERITY` are not logged.
+    pub fn emit_with_routing(&self, default_target: &str, alert_target: &str) {
+        if !crate::passes_env_filter(self.severity) {
+            return;
+        }
+        let level = log::Level::from(self.severity);
+        log::log!(target: default_target, level, "{}", self);
+        if self.severity == Severity::Critical {
+            log::log!(target: alert_target, level, "{}", self);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn severity_to_level() {
+        assert_eq!(log::Level::from(Severity::Critical), log::Level::Error);
+        assert_eq!(log::Level::from(Severity::Error), log::Level::Error);
+        assert_eq!(log::Level::from(Severity::Warning), log::Level::Warn);
+        assert_eq!(log::Level::from(Severity::Info), log::Level::Info);
+    }
+
+    #[test]
+    fn level_to_severity() {
+        assert_eq!(Severity::from(log::Level::Error), Severity::Error);
+        assert_eq!(Severity::from(log::Level::Warn), Severity::Warning);
+        assert_eq!(Severity::from(log::Level::Info), Severity::Info);
+        assert_eq!(Severity::from(log::Level::Debug), Severity::Info);
+        assert_eq!(Severity::from(log::Level::Trace), Severity::Info);
+    }
+
+    #[test]
+    fn round_trip_except_critical() {
+        for severity in [Severity::Error, Severity::Warning, Severity::Info] {
+            assert_eq!(Severity::from(log::Level::from(severity)), severity);
+        }
+        assert_eq!(
+            Severity::from(log::Level::from(Severity::Critical)),
+            Severity::Error
+        );
+    }
+
+    mod capture {
+        use std::sync::Mutex;
+
+        pub static RECORDS: Mutex<Vec<(String, log::Level, String)>> = Mutex::new(Vec::new());
+
+        struct Capture;
+
+        impl log::Log for Capture {
+            fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record<'_>) {
+                RECORDS.lock().unwrap().push((
+                    record.target().to_string(),
+                    record.level(),
+                    record.args().to_string(),
+                ));
+            }
+
+            fn flush(&self) {}
+        }
+
+        pub fn install() {
+            static LOGGER: Capture = Capture;
+            let _ = log::set_logger(&LOGGER);
+            log::set_max_level(log::LevelFilter::Trace);
+        }
+
+        /// Records whose message mentions `reference`, ignoring other tests' output.
+        pub fn records_for(reference: &str) -> Vec<(String, log::Level)> {
+            RECORDS
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(_, _, message)| message.contains(reference))
+                .map(|(target, level, _)| (target.clone(), *level))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn emit_routes_critical_to_alert_target() {
+        capture::install();
+        for err in [
+            Error::new(Severity::Critical, "ROUTE-CRIT", "Disk gone"),
+            Error::new(Severity::Error, "ROUTE-ERR", "Timeout"),
+            Error::new(Severity::Warning, "ROUTE-WARN", "Slow"),
+        ] {
+            err.emit_with_routing("app", "alerts");
+        }
+
+        assert_eq!(
+            capture::records_for("ROUTE-CRIT"),
+            [
+                ("app".to_string(), log::Level::Error),
+                ("alerts".to_string(), log::Level::Error)
+            ]
+        );
+        assert_eq!(
+            capture::records_for("ROUTE-ERR"),
+            [("app".to_string(), log::Level::Error)]
+        );
+        assert_eq!(
+            capture::records_for("ROUTE-WARN"),
+            [("app".to_string(), log::Level::Warn)]
+        );
+    }
+
+    #[test]
+    fn emit_logs_at_severity_level() {
+        capture::install();
+        Error::new(Severity::Error, "EMIT-ERR", "Timeout").emit();
+        assert_eq!(
+            capture::records_for("EMIT-ERR"),
+            [("mcp_error::log_bridge".to_string(), log::Level::Error)]
+        );
+    }
+
+    #[test]
+    fn emit_skips_errors_below_min_severity() {
+        let _lock = crate::tests::ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        capture::install();
+        std::env::set_var(crate::MIN_SEVERITY_ENV_VAR, "WARN");
+        Error::new(Severity::Info, "MIN-INFO", "Retrying").emit();
+        Error::new(Severity::Error, "MIN-ERR", "Timeout").emit();
+        Error::new(Severity::Info, "MIN-ROUTE", "Retrying").emit_with_routing("app", "alerts");
+        std::env::remove_var(crate::MIN_SEVERITY_ENV_VAR);
+
+        assert!(capture::records_for("MIN-INFO").is_empty());
+        assert!(capture::records_for("MIN-ROUTE").is_empty());
+        assert_eq!(
+            capture::records_for("MIN-ERR"),
+            [("mcp_error::log_bridge".to_string(), log::Level::Error)]
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Construction macros.
+
+/// Builds a `HashMap<String, String>` suitable for `Error::with_metadata_map`.
+///
+/// Keys are converted with `Into<String>` and values with `ToString`.
+///
+/// ```
+/// use mcp_error::{metadata, Error, Severity};
+///
+/// let err = Error::new(Severity::Warning, "DS-002", "Data parse incomplete")
+///     .with_metadata_map(metadata! { "filename" => "data.json", "line" => 42 });
+/// assert_eq!(err.metadata["line"], "42");
+/// ```
+#[macro_export]
+macro_rules! metadata {
+    () => {
+        ::std::collections::HashMap::<::std::string::String, ::std::string::String>::new()
+    };
+    ($($key:expr => $value:expr),+ $(,)?) => {{
+        let mut map =
+            ::std::collections::HashMap::<::std::string::String, ::std::string::String>::new();
+        $(
+            map.insert(
+                ::std::convert::Into::<::std::string::String>::into($key),
+                ::std::string::ToString::to_string(&$value),
+            );
+        )+
+        map
+    }};
+}
+
+/// Declares reference codes once as `pub const` strings.
+///
+/// Also generates `REFERENCES`, a table of every `(name, value)` pair declared
+/// by the invocation, so use it once per module. Declaring the same name twice
+/// fails to compile, and so does reusing the same reference value:
+///
+/// ```
+/// mod refs {
+///     mcp_error::define_references! {
+///         NET_TIMEOUT = "NET-001",
+///         FS_NOT_FOUND = "FSY-404",
+///     }
+/// }
+///
+/// let err = mcp_error::Error::new(mcp_error::Severity::Error, refs::NET_TIMEOUT, "Timeout");
+/// assert_eq!(err.reference, "NET-001");
+/// assert_eq!(refs::REFERENCES.len(), 2);
+/// ```
+///
+/// ```compile_fail
+/// mcp_error::define_references! {
+///     NET_TIMEOUT = "NET-001",
+///     NET_SLOW = "NET-001",
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_references {
+    ($($name:ident = $value:expr),+ $(,)?) => {
+        $(
+            pub const $name: &str = $value;
+        )+
+
+        /// Every reference declared by this `define_references!` invocation.
+        pub const REFERENCES: &[(&str, &str)] = &[$((stringify!($name), $value)),+];
+
+        const _: () = assert!(
+            $crate::__references_unique(&[$($value),+]),
+            "duplicate reference value in define_references!"
+        );
+    };
+}
+
+/// Returns early with an `Error` of the given severity variant.
+///
+/// The description accepts `format!` arguments:
+/// `bail_mcp!(Error, "NET-001", "timeout contacting {}", host)`.
+#[macro_export]
+macro_rules! bail_mcp {
+    ($severity:ident, $reference:expr, $($arg:tt)+) => {
+        return ::std::result::Result::Err($crate::Error::new(
+            $crate::Severity::$severity,
+            $reference,
+            ::std::format!($($arg)+),
+        ))
+    };
+}
+
+/// Returns early with an `Error` when `cond` is false, like `anyhow::ensure!`.
+///
+/// `ensure_mcp!(len > 0, Warning, "DS-002", "empty payload from {}", peer)`.
+#[macro_export]
+macro_rules! ensure_mcp {
+    ($cond:expr, $severity:ident, $reference:expr, $($arg:tt)+) => {
+        if !$cond {
+            $crate::bail_mcp!($severity, $reference, $($arg)+);
+        }
+    };
+}
+
+/// Compile-time check used by `define_references!`; not part of the public API.
+#[doc(hidden)]
+pub const fn __references_unique(values: &[&str]) -> bool {
+    let mut i = 0;
+    while i < values.len() {
+        let mut j = i + 1;
+        while j < values.len() {
+            if const_str_eq(values[i], values[j]) {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn const_str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, Result, Severity};
+
+    mod refs {
+        define_references! {
+            NET_TIMEOUT = "NET-001",
+            FS_NOT_FOUND = "FSY-404",
+        }
+    }
+
+    #[test]
+    fn metadata_macro_builds_map() {
+        let host = String::from("db.local");
+        let map = metadata! {
+            "filename" => "data.json",
+            "line" => 42,
+            String::from("host") => host,
+        };
+        assert_eq!(map.len(), 3);
+        assert_eq!(map["filename"], "data.json");
+        assert_eq!(map["line"], "42");
+        assert_eq!(map["host"], "db.local");
+    }
+
+    #[test]
+    fn metadata_macro_empty() {
+        assert!(metadata! {}.is_empty());
+    }
+
+    #[test]
+    fn with_metadata_map_merges_entries() {
+        let err = Error::new(Severity::Warning, "DS-002", "Data parse incomplete")
+            .insert_metadata("line", "1")
+            .insert_metadata("column", "7")
+            .with_metadata_map(metadata! { "filename" => "data.json", "line" => 42 });
+        assert_eq!(err.metadata.len(), 3);
+        assert_eq!(err.metadata["line"], "42");
+        assert_eq!(err.metadata["column"], "7");
+    }
+
+    #[test]
+    fn define_references_generates_constants() {
+        assert_eq!(refs::NET_TIMEOUT, "NET-001");
+        assert_eq!(refs::FS_NOT_FOUND, "FSY-404");
+        assert_eq!(
+            refs::REFERENCES,
+            &[("NET_TIMEOUT", "NET-001"), ("FS_NOT_FOUND", "FSY-404")]
+        );
+
+        let err = Error::new(Severity::Error, refs::NET_TIMEOUT, "Timeout");
+        assert_eq!(err.reference, "NET-001");
+    }
+
+    #[test]
+    fn duplicate_reference_values_are_detected() {
+        assert!(crate::__references_unique(&["NET-001", "FSY-404"]));
+        assert!(!crate::__references_unique(&["NET-001", "FSY-404", "NET-001"]));
+    }
+
+    fn connect(host: &str) -> Result<()> {
+        if host.is_empty() {
+            bail_mcp!(Error, "NET-001", "timeout contacting {:?}", host);
+        }
+        Ok(())
+    }
+
+    fn parse(len: usize) -> Result<usize> {
+        ensure_mcp!(len > 0, Warning, "DS-002", "empty payload");
+        ensure_mcp!(len < 10, Critical, "DS-003", "payload too large: {}", len);
+        Ok(len)
+    }
+
+    #[test]
+    fn bail_mcp_returns_early() {
+        assert!(connect("db.local").is_ok());
+
+        let err = connect("").unwrap_err();
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(err.reference, "NET-001");
+        assert_eq!(err.description, "timeout contacting \"\"");
+    }
+
+    #[test]
+    fn ensure_mcp_checks_condition() {
+        assert_eq!(parse(3).unwrap(), 3);
+
+        let err = parse(0).unwrap_err();
+        assert_eq!(err.severity, Severity::Warning);
+        assert_eq!(err.reference, "DS-002");
+        assert_eq!(err.description, "empty payload");
+
+        let err = parse(12).unwrap_err();
+        assert_eq!(err.severity, Severity::Critical);
+        assert_eq!(err.description, "payload too large: 12");
+    }
+}
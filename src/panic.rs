@@ -0,0 +1,75 @@
+//! Panic hook rendering panics in the `Error` format.
+
+use std::any::Any;
+use std::panic::{self, PanicHookInfo};
+
+use crate::{Error, Severity};
+
+/// Reference given to errors built from a panic.
+pub const PANIC_REFERENCE: &str = "PANIC";
+
+/// Installs a panic hook printing every panic to stderr as a `Critical` `Error`.
+///
+/// The panic message becomes the description and the panic location, when
+/// known, is stored in the `location` metadata entry as `file:line:column`.
+/// This replaces any previously installed hook.
+pub fn install_panic_hook() {
+    panic::set_hook(Box::new(|info: &PanicHookInfo<'_>| {
+        let location = info
+            .location()
+            .map(|loc| format!("{}:{}:{}", loc.file(), loc.line(), loc.column()));
+        eprintln!("{}", panic_error(payload_message(info.payload()), location));
+    }));
+}
+
+/// Extracts the human-readable message of a panic payload.
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+/// Builds the `Error` reported for a panic.
+fn panic_error(message: String, location: Option<String>) -> Error {
+    let err = Error::new(Severity::Critical, PANIC_REFERENCE, message);
+    match location {
+        Some(location) => err.insert_metadata("location", location),
+        None => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panic_error_carries_message_and_location() {
+        let err = panic_error("index out of bounds".into(), Some("src/main.rs:10:5".into()));
+        assert_eq!(err.severity, Severity::Critical);
+        assert_eq!(err.reference, "PANIC");
+        assert_eq!(err.description, "index out of bounds");
+        assert_eq!(err.metadata["location"], "src/main.rs:10:5");
+        assert_eq!(err.to_string(), "[CRIT] Ref: PANIC | index out of bounds");
+    }
+
+    #[test]
+    fn panic_error_without_location() {
+        let err = panic_error("boom".into(), None);
+        assert!(err.metadata.is_empty());
+    }
+
+    #[test]
+    fn payload_message_handles_str_and_string() {
+        let static_payload: Box<dyn Any + Send> = Box::new("static message");
+        let owned_payload: Box<dyn Any + Send> = Box::new(String::from("owned message"));
+        let other_payload: Box<dyn Any + Send> = Box::new(42_u8);
+
+        assert_eq!(payload_message(static_payload.as_ref()), "static message");
+        assert_eq!(payload_message(owned_payload.as_ref()), "owned message");
+        assert_eq!(payload_message(other_payload.as_ref()), "Box<dyn Any>");
+    }
+}
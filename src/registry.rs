@@ -0,0 +1,113 @@
+//! Canonical severity and description per reference, kept in one place.
+
+use std::collections::HashMap;
+
+use crate::{Error, Result, Severity};
+
+/// Reference of the error returned by `Error::from_registry` for an unknown reference.
+pub const UNKNOWN_REFERENCE: &str = "MCP-UNKNOWN-REF";
+
+/// Canonical information about one reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefInfo {
+    pub severity: Severity,
+    pub description: String,
+    /// Remediation page, attached with `Error::with_docs_url` when set.
+    pub docs_url: Option<String>,
+}
+
+impl RefInfo {
+    /// Creates an entry without a docs link.
+    pub fn new(severity: Severity, description: impl Into<String>) -> Self {
+        Self {
+            severity,
+            description: description.into(),
+            docs_url: None,
+        }
+    }
+
+    /// Sets the remediation page for the reference.
+    pub fn with_docs_url(mut self, url: impl Into<String>) -> Self {
+        self.docs_url = Some(url.into());
+        self
+    }
+}
+
+/// Maps reference codes to their canonical `RefInfo`.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceRegistry {
+    entries: HashMap<String, RefInfo>,
+}
+
+impl ReferenceRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers (or replaces) the entry for `reference`.
+    pub fn register(mut self, reference: impl Into<String>, info: RefInfo) -> Self {
+        self.entries.insert(reference.into(), info);
+        self
+    }
+
+    /// Returns the entry for `reference`, if registered.
+    pub fn get(&self, reference: &str) -> Option<&RefInfo> {
+        self.entries.get(reference)
+    }
+}
+
+impl Error {
+    /// Builds the canonical error for `reference` from `registry`.
+    ///
+    /// An unregistered reference is rejected with an `UNKNOWN_REFERENCE` error,
+    /// so references and their docs cannot silently drift apart.
+    pub fn from_registry(registry: &ReferenceRegistry, reference: &str) -> Result<Error> {
+        let Some(info) = registry.get(reference) else {
+            return Err(Error::new(
+                Severity::Error,
+                UNKNOWN_REFERENCE,
+                format!("Reference is not registered: {:?}", reference),
+            ));
+        };
+        let err = Error::new(info.severity, reference, info.description.clone());
+        Ok(match &info.docs_url {
+            Some(url) => err.with_docs_url(url.clone()),
+            None => err,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> ReferenceRegistry {
+        ReferenceRegistry::new()
+            .register("NET-001", RefInfo::new(Severity::Error, "Connection timed out"))
+            .register(
+                "FSY-404",
+                RefInfo::new(Severity::Warning, "File not found")
+                    .with_docs_url("https://docs.example.com/FSY-404"),
+            )
+    }
+
+    #[test]
+    fn registered_reference_uses_canonical_fields() {
+        let err = Error::from_registry(&registry(), "NET-001").unwrap();
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(err.reference, "NET-001");
+        assert_eq!(err.description, "Connection timed out");
+
+        let err = Error::from_registry(&registry(), "FSY-404").unwrap();
+        assert_eq!(err.severity, Severity::Warning);
+        assert_eq!(err.docs_url(""), "https://docs.example.com/FSY-404");
+    }
+
+    #[test]
+    fn unknown_reference_is_rejected() {
+        let err = Error::from_registry(&registry(), "NET-999").unwrap_err();
+        assert_eq!(err.reference, UNKNOWN_REFERENCE);
+        assert!(err.description.contains("NET-999"));
+    }
+}
@@ -0,0 +1,65 @@
+//! Deployment-wide severity policies applied on top of call-site severities.
+
+use std::collections::HashMap;
+
+use crate::{Error, Severity};
+
+/// A severity rewrite table, e.g. treating every `Warning` as an `Error` in
+/// strict mode. Severities without an entry are left as they are.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeverityRemap {
+    mapping: HashMap<Severity, Severity>,
+}
+
+impl SeverityRemap {
+    /// Creates an empty remap that leaves every severity untouched.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rewrites `from` to `to`, replacing any previous entry for `from`.
+    pub fn map(mut self, from: Severity, to: Severity) -> Self {
+        self.mapping.insert(from, to);
+        self
+    }
+
+    /// Returns the severity `severity` is rewritten to.
+    pub fn apply(&self, severity: Severity) -> Severity {
+        self.mapping.get(&severity).copied().unwrap_or(severity)
+    }
+}
+
+impl Error {
+    /// Rewrites the severity according to `remap`.
+    pub fn apply_remap(mut self, remap: &SeverityRemap) -> Self {
+        self.severity = remap.apply(self.severity);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaps_only_mapped_severities() {
+        let strict = SeverityRemap::new().map(Severity::Warning, Severity::Error);
+
+        let err = Error::warning("NET-002", "Slow response").apply_remap(&strict);
+        assert_eq!(err.severity, Severity::Error);
+
+        for severity in [Severity::Critical, Severity::Error, Severity::Info] {
+            let err = Error::new(severity, "NET-001", "Timeout").apply_remap(&strict);
+            assert_eq!(err.severity, severity);
+        }
+    }
+
+    #[test]
+    fn remap_is_applied_once() {
+        let remap = SeverityRemap::new()
+            .map(Severity::Info, Severity::Warning)
+            .map(Severity::Warning, Severity::Error);
+        let err = Error::info("NET-001", "Connected").apply_remap(&remap);
+        assert_eq!(err.severity, Severity::Warning);
+    }
+}
@@ -0,0 +1,696 @@
+//! Alternative text renderings of `Error` for specific sinks.
+
+use std::fmt::{self, Write};
+use std::io::IsTerminal;
+
+use crate::{Error, Severity, ANSI_RESET, DISPLAY_SEPARATOR};
+
+/// Decides whether colored output is used.
+///
+/// Precedence: the explicit `choice`, then `NO_COLOR` (set and non-empty
+/// disables colors), then `CLICOLOR_FORCE` (set and not `0` forces them),
+/// and finally whether stderr is a terminal.
+pub fn color_enabled(choice: Option<bool>) -> bool {
+    resolve_color(
+        choice,
+        std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
+        std::env::var_os("CLICOLOR_FORCE").is_some_and(|value| value != "0"),
+        std::io::stderr().is_terminal(),
+    )
+}
+
+fn resolve_color(choice: Option<bool>, no_color: bool, force: bool, is_terminal: bool) -> bool {
+    match choice {
+        Some(choice) => choice,
+        None if no_color => false,
+        None => force || is_terminal,
+    }
+}
+
+impl Severity {
+    /// Friendly symbol for user-facing CLIs: Critical→⛔, Error→❌, Warning→⚠️, Info→ℹ️.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Severity::Critical => "⛔",
+            Severity::Error => "❌",
+            Severity::Warning => "⚠️",
+            Severity::Info => "ℹ️",
+        }
+    }
+
+    /// RFC 5424 severity code: Critical→2, Error→3, Warning→4, Info→6.
+    pub fn syslog_code(&self) -> u8 {
+        match self {
+            Severity::Critical => 2,
+            Severity::Error => 3,
+            Severity::Warning => 4,
+            Severity::Info => 6,
+        }
+    }
+}
+
+impl Error {
+    /// Like `Display`, with `Severity::symbol` in place of the `[SEV]` tag:
+    /// `❌ Ref: NET-001 | Timeout`.
+    pub fn to_friendly_string(&self) -> String {
+        format!(
+            "{} Ref: {} | {}{}",
+            self.severity.symbol(),
+            self.reference,
+            self.description,
+            SourceSuffix(self)
+        )
+    }
+
+    /// The minimal rendering for end users: only the description, without the
+    /// severity tag, reference or source chain.
+    pub fn to_message_only(&self) -> String {
+        self.description.clone()
+    }
+
+    /// Like `Display`, with `sep` in place of the ` | ` between the parts, for
+    /// log parsers that split on a delimiter descriptions are unlikely to
+    /// contain: `[ERR] Ref: NET-001\tTimeout\tSource: io`.
+    pub fn display_with_separator(&self, sep: &str) -> String {
+        SeparatedDisplay(self, sep).to_string()
+    }
+
+    /// Like `Display`, with the severity tag colored using `Severity::ansi_code`
+    /// when [`color_enabled`] agrees; `color` overrides the environment.
+    pub fn to_colored_string(&self, color: Option<bool>) -> String {
+        if !color_enabled(color) {
+            return self.to_string();
+        }
+        let mut out = format!(
+            "{}[{}]{} Ref: {} | {}",
+            self.severity.ansi_code(),
+            self.severity,
+            ANSI_RESET,
+            self.reference,
+            self.description
+        );
+        let _ = write!(out, "{}", SourceSuffix(self));
+        out
+    }
+
+    /// Renders a boxed, multi-line report for human readers, e.g. a CLI `--explain`:
+    ///
+    /// ```text
+    /// ┌─ [ERR] Error
+    /// │ Reference:   CFG-001
+    /// │ Description: Cannot load config
+    /// │ Metadata:
+    /// │   filename = config.toml
+    /// │ Caused by:
+    /// │   1. [FSY-404] Cannot read file
+    /// │   2. File not found
+    /// └─
+    /// ```
+    ///
+    /// Metadata is listed in key order; `Error` causes show their reference. A
+    /// chain cut short ends with `Chain::end_marker` as its last cause.
+    /// Breadcrumbs from [`Error::push_context`] appear as a `Context:` trail.
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "┌─ [{}] {}", self.severity, self.severity.name());
+        let _ = writeln!(out, "│ Reference:   {}", self.reference);
+        let _ = writeln!(out, "│ Description: {}", self.description);
+        if !self.context.is_empty() {
+            let _ = writeln!(out, "│ Context:     {}", self.context.join(" > "));
+        }
+        if let Some(retry_after) = self.retry_after() {
+            let _ = writeln!(out, "│ Retry after: {:?}", retry_after);
+        }
+        if let Some(docs_url) = &self.docs_url {
+            let _ = writeln!(out, "│ Docs:        {}", docs_url);
+        }
+
+        if !self.metadata.is_empty() {
+            let mut entries: Vec<_> = self.metadata.iter().collect();
+            entries.sort();
+            let _ = writeln!(out, "│ Metadata:");
+            for (key, value) in entries {
+                let _ = writeln!(out, "│   {} = {}", key, value);
+            }
+        }
+
+        let mut chain = self.chain();
+        chain.next();
+        let mut causes: Vec<String> = chain
+            .by_ref()
+            .map(|cause| match cause.downcast_ref::<Error>() {
+                Some(err) => format!("[{}] {}", err.reference, err.description),
+                None => cause.to_string(),
+            })
+            .collect();
+        causes.extend(chain.end_marker().map(String::from));
+        if !causes.is_empty() {
+            let _ = writeln!(out, "│ Caused by:");
+            for (i, cause) in causes.iter().enumerate() {
+                let _ = writeln!(out, "│   {}. {}", i + 1, cause);
+            }
+        }
+
+        out.push_str("└─");
+        out
+    }
+
+    /// Like `Display`, with the metadata inlined after the description in key order:
+    /// `[ERR] Ref: NET-001 | Timeout | {filename=data.json, line=42}`.
+    ///
+    /// Nothing is added when there is no metadata.
+    pub fn to_verbose_string(&self) -> String {
+        VerboseDisplay(self).to_string()
+    }
+
+    /// Like `Display`, with repeated consecutive chain messages shown once
+    /// (see [`Error::dedup_chain`]). The description counts as the first message.
+    pub fn to_deduped_string(&self) -> String {
+        let mut out = format!("[{}] Ref: {}", self.severity, self.reference);
+        let messages = self.dedup_chain();
+        if let Some((description, sources)) = messages.split_first() {
+            let _ = write!(out, " | {}", description);
+            if !self.context.is_empty() {
+                let _ = write!(out, " | Context: {}", self.context.join(" > "));
+            }
+            for source in sources {
+                let _ = write!(out, " | Source: {}", source);
+            }
+        }
+        out
+    }
+
+    /// Fills `{key}` placeholders in the description from `metadata`:
+    /// `"Failed to read {filename}"` becomes `"Failed to read config.toml"`.
+    ///
+    /// Placeholders without a matching key, and unbalanced braces, are kept
+    /// literally so a missing value stays visible.
+    pub fn render_description(&self) -> String {
+        let mut out = String::with_capacity(self.description.len());
+        let mut rest = self.description.as_str();
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            match after.find(['{', '}']) {
+                Some(close) if after.as_bytes()[close] == b'}' => {
+                    let key = &after[..close];
+                    match self.metadata.get(key) {
+                        Some(value) => out.push_str(value),
+                        None => out.push_str(&rest[open..open + close + 2]),
+                    }
+                    rest = &after[close + 1..];
+                }
+                _ => {
+                    out.push('{');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Renders the `Display` content hard-wrapped at `cols` characters for
+    /// fixed-width panels:
+    ///
+    /// ```text
+    /// [ERR] Ref: CFG-001 | Cannot load
+    ///   the configuration file
+    /// Source: File not found
+    /// ```
+    ///
+    /// The header with the description comes first, then one `Source:` line
+    /// per chain level. Lines break on whitespace, continuation lines are
+    /// indented by two spaces, and words longer than a line are split.
+    pub fn format_for_width(&self, cols: usize) -> String {
+        let cols = cols.max(1);
+        let mut paragraphs = vec![format!(
+            "[{}] Ref: {} | {}",
+            self.severity, self.reference, self.description
+        )];
+        if !self.context.is_empty() {
+            paragraphs.push(format!("Context: {}", self.context.join(" > ")));
+        }
+        let mut chain = self.chain();
+        chain.next();
+        paragraphs.extend(
+            chain
+                .by_ref()
+                .map(|err| format!("Source: {}", crate::chain_message(err))),
+        );
+        if let Some(marker) = chain.end_marker() {
+            paragraphs.push(format!("Source: {}", marker));
+        }
+
+        let mut lines = Vec::new();
+        for paragraph in &paragraphs {
+            wrap_paragraph(&mut lines, paragraph, cols);
+        }
+        lines.join("\n")
+    }
+
+    /// Formats the error as a GitHub Actions workflow command, so CI shows it
+    /// inline: `::error file=src/main.rs,line=42,title=CFG-001::Cannot load config`.
+    ///
+    /// `Critical` and `Error` map to `::error`, `Warning` to `::warning` and
+    /// `Info` to `::notice`. The position comes from the `file`, `line` and
+    /// `col` metadata, or else from a `file:line[:col]` `location` entry as
+    /// recorded by the panic hook. The reference is the title; the message is
+    /// the description followed by the sources, as in `Display`.
+    pub fn to_gha_annotation(&self) -> String {
+        let command = match self.severity {
+            Severity::Critical | Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "notice",
+        };
+
+        let mut properties = Vec::new();
+        match self.metadata.get("file") {
+            Some(file) => {
+                properties.push(("file", file.as_str()));
+                for key in ["line", "col"] {
+                    if let Some(value) = self.metadata.get(key) {
+                        properties.push((key, value.as_str()));
+                    }
+                }
+            }
+            None => {
+                if let Some(location) = self.metadata.get("location") {
+                    properties.extend(parse_location(location));
+                }
+            }
+        }
+        if !self.reference.is_empty() {
+            properties.push(("title", self.reference.as_str()));
+        }
+
+        let mut out = format!("::{}", command);
+        for (i, (key, value)) in properties.iter().enumerate() {
+            let sep = if i == 0 { ' ' } else { ',' };
+            let _ = write!(out, "{}{}={}", sep, key, escape_gha_property(value));
+        }
+        let message = format!("{}{}", self.description, SourceSuffix(self));
+        let _ = write!(out, "::{}", escape_gha_data(&message));
+        out
+    }
+
+    /// Formats the error as an RFC 5424 syslog line.
+    ///
+    /// The PRI is `facility * 8 + severity` (facilities above 23 are clamped
+    /// to 23). Timestamp, hostname, app name and proc id are left as the nil
+    /// value `-` for the daemon to fill in; the reference is used as MSGID
+    /// when it is valid there (1-32 printable ASCII characters, no spaces).
+    pub fn to_syslog_line(&self, facility: u8) -> String {
+        let pri = u16::from(facility.min(23)) * 8 + u16::from(self.severity.syslog_code());
+        let msgid = if !self.reference.is_empty()
+            && self.reference.len() <= 32
+            && self.reference.bytes().all(|b| b.is_ascii_graphic())
+        {
+            self.reference.as_str()
+        } else {
+            "-"
+        };
+        format!("<{}>1 - - - - {} - {}", pri, msgid, self)
+    }
+}
+
+/// Wraps `text` into `lines`, indenting continuation lines when there is room.
+fn wrap_paragraph(lines: &mut Vec<String>, text: &str, cols: usize) {
+    let indent = if cols > 8 { "  " } else { "" };
+    let mut line = String::new();
+    let mut len = 0;
+    for word in text.split_whitespace() {
+        let word_len = word.chars().count();
+        if len > 0 && len + 1 + word_len <= cols {
+            line.push(' ');
+            line.push_str(word);
+            len += 1 + word_len;
+            continue;
+        }
+        if len > 0 {
+            lines.push(std::mem::take(&mut line));
+            line.push_str(indent);
+            len = indent.len();
+        }
+        // Hard-break words that do not fit on a line of their own.
+        let mut chars = word.chars().peekable();
+        while chars.peek().is_some() {
+            if len == cols {
+                lines.push(std::mem::take(&mut line));
+                line.push_str(indent);
+                len = indent.len();
+            }
+            let chunk: String = chars.by_ref().take(cols - len).collect();
+            len += chunk.chars().count();
+            line.push_str(&chunk);
+        }
+    }
+    if len > 0 {
+        lines.push(line);
+    }
+}
+
+/// Splits a `file:line[:col]` location; the file itself may contain colons.
+fn parse_location(location: &str) -> Vec<(&'static str, &str)> {
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let parts: Vec<&str> = location.rsplitn(3, ':').collect();
+    match parts.as_slice() {
+        [col, line, file] if is_number(line) && is_number(col) => {
+            vec![("file", *file), ("line", *line), ("col", *col)]
+        }
+        [line, rest @ ..] if is_number(line) && !rest.is_empty() => {
+            let file = &location[..location.len() - line.len() - 1];
+            vec![("file", file), ("line", *line)]
+        }
+        _ => vec![("file", location)],
+    }
+}
+
+/// Escapes a workflow command message.
+fn escape_gha_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a workflow command property value.
+fn escape_gha_property(value: &str) -> String {
+    escape_gha_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+struct SourceSuffix<'a>(&'a Error);
+
+impl fmt::Display for SourceSuffix<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_context(f, DISPLAY_SEPARATOR)?;
+        self.0.fmt_source(f, DISPLAY_SEPARATOR)
+    }
+}
+
+struct SeparatedDisplay<'a>(&'a Error, &'a str);
+
+impl fmt::Display for SeparatedDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let SeparatedDisplay(err, sep) = *self;
+        write!(
+            f,
+            "[{}] Ref: {}{}{}",
+            err.severity, err.reference, sep, err.description
+        )?;
+        err.fmt_context(f, sep)?;
+        err.fmt_source(f, sep)
+    }
+}
+
+struct VerboseDisplay<'a>(&'a Error);
+
+impl fmt::Display for VerboseDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let err = self.0;
+        write!(
+            f,
+            "[{}] Ref: {} | {}",
+            err.severity, err.reference, err.description
+        )?;
+        if !err.metadata.is_empty() {
+            let mut entries: Vec<_> = err.metadata.iter().collect();
+            entries.sort();
+            write!(f, " | {{")?;
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}={}", key, value)?;
+            }
+            write!(f, "}}")?;
+        }
+        err.fmt_context(f, DISPLAY_SEPARATOR)?;
+        err.fmt_source(f, DISPLAY_SEPARATOR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn syslog_pri_per_severity() {
+        // Facility 16 is local0.
+        let pri = |severity| {
+            let line = Error::new(severity, "NET-001", "Timeout").to_syslog_line(16);
+            line[1..line.find('>').unwrap()].parse::<u16>().unwrap()
+        };
+        assert_eq!(pri(Severity::Critical), 130);
+        assert_eq!(pri(Severity::Error), 131);
+        assert_eq!(pri(Severity::Warning), 132);
+        assert_eq!(pri(Severity::Info), 134);
+    }
+
+    #[test]
+    fn syslog_line_format() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout");
+        assert_eq!(
+            err.to_syslog_line(1),
+            "<11>1 - - - - NET-001 - [ERR] Ref: NET-001 | Timeout"
+        );
+
+        let err = Error::new(Severity::Info, "bad ref", "Timeout");
+        assert!(err.to_syslog_line(200).starts_with("<190>1 - - - - - - "));
+    }
+
+    #[test]
+    fn verbose_string_includes_sorted_metadata() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout")
+            .insert_metadata("line", "42")
+            .insert_metadata("filename", "data.json");
+        assert_eq!(
+            err.to_verbose_string(),
+            "[ERR] Ref: NET-001 | Timeout | {filename=data.json, line=42}"
+        );
+        assert_eq!(err.to_string(), "[ERR] Ref: NET-001 | Timeout");
+    }
+
+    #[test]
+    fn verbose_string_without_metadata_matches_display() {
+        let err = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(std::io::Error::other("denied")));
+        assert_eq!(err.to_verbose_string(), err.to_string());
+    }
+
+    #[test]
+    fn report_contains_sections() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "File not found");
+        let inner = Error::new(Severity::Error, "FSY-404", "Cannot read file")
+            .with_source(Box::new(io_err));
+        let err = Error::new(Severity::Critical, "CFG-001", "Cannot load config")
+            .insert_metadata("filename", "config.toml")
+            .insert_metadata("attempt", "2")
+            .with_source(Box::new(inner));
+
+        let report = err.report();
+        assert!(report.starts_with("┌─ [CRIT] Critical\n"));
+        assert!(report.contains("│ Reference:   CFG-001\n"));
+        assert!(report.contains("│ Description: Cannot load config\n"));
+        assert!(report.contains("│ Metadata:\n│   attempt = 2\n│   filename = config.toml\n"));
+        assert!(report.contains("│ Caused by:\n"));
+        assert!(report.contains("│   1. [FSY-404] Cannot read file\n"));
+        assert!(report.contains("│   2. File not found\n"));
+        assert!(report.ends_with("└─"));
+    }
+
+    #[test]
+    fn report_marks_truncated_chain() {
+        let levels = crate::max_chain_depth() + 3;
+        let err = Error::new(Severity::Error, "DEEP-001", "Deep failure")
+            .with_cause_messages((0..levels).map(|i| format!("level {}", i)));
+
+        let report = err.report();
+        let depth = crate::max_chain_depth();
+        assert!(report.contains(&format!("│   {}. level {}\n", depth, depth - 1)));
+        assert!(report.contains(&format!("│   {}. {}\n", depth + 1, crate::TRUNCATED_MARKER)));
+        assert!(!report.contains(&format!("level {}", depth)));
+    }
+
+    #[test]
+    fn report_omits_empty_sections() {
+        let report = Error::new(Severity::Info, "NET-100", "Retrying").report();
+        assert!(!report.contains("Metadata:"));
+        assert!(!report.contains("Caused by:"));
+        assert_eq!(report.lines().count(), 4);
+    }
+
+    #[test]
+    fn color_resolution_precedence() {
+        assert!(resolve_color(Some(true), true, false, false));
+        assert!(!resolve_color(Some(false), false, true, true));
+        assert!(!resolve_color(None, true, true, true));
+        assert!(resolve_color(None, false, true, false));
+        assert!(resolve_color(None, false, false, true));
+        assert!(!resolve_color(None, false, false, false));
+    }
+
+    #[test]
+    fn colored_string_respects_no_color() {
+        let _lock = crate::tests::ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let err = Error::new(Severity::Error, "NET-001", "Timeout");
+
+        std::env::remove_var("CLICOLOR_FORCE");
+        std::env::set_var("NO_COLOR", "1");
+        assert_eq!(err.to_colored_string(None), "[ERR] Ref: NET-001 | Timeout");
+        assert!(err.to_colored_string(Some(true)).contains("\x1b["));
+
+        std::env::remove_var("NO_COLOR");
+        std::env::set_var("CLICOLOR_FORCE", "1");
+        assert_eq!(
+            err.to_colored_string(None),
+            "\x1b[31m[ERR]\x1b[0m Ref: NET-001 | Timeout"
+        );
+        assert!(!err.to_colored_string(Some(false)).contains('\x1b'));
+        std::env::remove_var("NO_COLOR");
+        std::env::remove_var("CLICOLOR_FORCE");
+    }
+
+    #[test]
+    fn severity_symbols() {
+        assert_eq!(Severity::Critical.symbol(), "⛔");
+        assert_eq!(Severity::Error.symbol(), "❌");
+        assert_eq!(Severity::Warning.symbol(), "⚠️");
+        assert_eq!(Severity::Info.symbol(), "ℹ️");
+    }
+
+    #[test]
+    fn friendly_string_uses_symbol() {
+        let err = Error::new(Severity::Warning, "DS-002", "Data parse incomplete");
+        assert_eq!(err.to_friendly_string(), "⚠️ Ref: DS-002 | Data parse incomplete");
+        assert_eq!(err.to_string(), "[WARN] Ref: DS-002 | Data parse incomplete");
+    }
+
+    #[test]
+    fn report_lists_context_in_push_order() {
+        let report = Error::new(Severity::Error, "CFG-002", "Invalid port")
+            .push_context("while loading config")
+            .push_context("while parsing section [db]")
+            .push_context("while reading key port")
+            .report();
+        assert!(report.contains(
+            "│ Context:     while loading config > while parsing section [db] \
+             > while reading key port\n"
+        ));
+    }
+
+    #[test]
+    fn render_description_fills_placeholders() {
+        let err = Error::new(Severity::Error, "FSY-001", "Failed to read {filename} at line {line}")
+            .insert_metadata("filename", "data.json")
+            .insert_metadata("line", "42");
+        assert_eq!(err.render_description(), "Failed to read data.json at line 42");
+    }
+
+    #[test]
+    fn render_description_keeps_unknown_placeholders() {
+        let err = Error::new(Severity::Error, "FSY-001", "Failed to read {filename} ({mode}")
+            .insert_metadata("filename", "data.json");
+        assert_eq!(err.render_description(), "Failed to read data.json ({mode}");
+
+        let err = Error::new(Severity::Error, "FSY-001", "Bad {{filename} or {user}")
+            .insert_metadata("filename", "data.json");
+        assert_eq!(err.render_description(), "Bad {data.json or {user}");
+    }
+
+    #[test]
+    fn render_description_without_placeholders_is_unchanged() {
+        let err = Error::new(Severity::Error, "FSY-001", "Failed to read file")
+            .insert_metadata("filename", "data.json");
+        assert_eq!(err.render_description(), "Failed to read file");
+    }
+
+    #[test]
+    fn format_for_width_stays_within_cols() {
+        let err = Error::new(
+            Severity::Error,
+            "CFG-001",
+            "Cannot load the configuration file from the user directory",
+        )
+        .with_source(Box::new(std::io::Error::other("File not found on disk")));
+        for cols in [12, 20, 33, 80] {
+            let out = err.format_for_width(cols);
+            assert!(out.lines().all(|line| line.chars().count() <= cols), "{cols}: {out}");
+        }
+        assert_eq!(
+            err.format_for_width(32),
+            "[ERR] Ref: CFG-001 | Cannot load\n  the configuration file from\n  \
+             the user directory\nSource: File not found on disk"
+        );
+    }
+
+    #[test]
+    fn format_for_width_breaks_long_words() {
+        let err = Error::new(Severity::Error, "FSY-404", "/very/long/path/to/a/missing/file.txt");
+        let out = err.format_for_width(16);
+        assert_eq!(
+            out,
+            "[ERR] Ref:\n  FSY-404 |\n  /very/long/pat\n  h/to/a/missing\n  /file.txt"
+        );
+        assert!(out.lines().all(|line| line.chars().count() <= 16));
+    }
+
+    #[test]
+    fn report_shows_docs_url() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout");
+        assert!(!err.report().contains("Docs:"));
+        let report = err.with_docs_url("https://docs.example.com/NET-001").report();
+        assert!(report.contains("│ Docs:        https://docs.example.com/NET-001\n"));
+    }
+
+    #[test]
+    fn gha_annotation_with_file_and_line() {
+        let err = Error::new(Severity::Error, "CFG-001", "Cannot load config")
+            .insert_metadata("file", "conf/app,v1.toml")
+            .insert_metadata("line", "42")
+            .with_source(Box::new(std::io::Error::other("50% done\nthen failed")));
+        assert_eq!(
+            err.to_gha_annotation(),
+            "::error file=conf/app%2Cv1.toml,line=42,title=CFG-001::\
+             Cannot load config | Source: 50%25 done%0Athen failed"
+        );
+
+        let err = Error::new(Severity::Critical, "PANIC", "boom")
+            .insert_metadata("location", "C:/src/main.rs:10:5");
+        assert_eq!(
+            err.to_gha_annotation(),
+            "::error file=C%3A/src/main.rs,line=10,col=5,title=PANIC::boom"
+        );
+    }
+
+    #[test]
+    fn gha_annotation_without_position() {
+        let err = Error::new(Severity::Warning, "NET-002", "Slow response");
+        assert_eq!(err.to_gha_annotation(), "::warning title=NET-002::Slow response");
+
+        let err = Error::new(Severity::Info, "", "Cache warmed");
+        assert_eq!(err.to_gha_annotation(), "::notice::Cache warmed");
+    }
+
+    #[test]
+    fn message_only_drops_tag_and_reference() {
+        let err = Error::new(Severity::Error, "NET-001", "Connection timed out")
+            .with_source(Box::new(std::io::Error::other("socket closed")));
+        let message = err.to_message_only();
+        assert_eq!(message, "Connection timed out");
+        assert!(!message.contains("Ref:"));
+        assert!(!message.contains("[ERR]"));
+    }
+
+    #[test]
+    fn display_with_custom_separator() {
+        let err = Error::new(Severity::Error, "NET-001", "Timeout | retried")
+            .with_source(Box::new(std::io::Error::other("socket closed")))
+            .push_context("fetch");
+        assert_eq!(
+            err.display_with_separator("\t"),
+            "[ERR] Ref: NET-001\tTimeout | retried\tContext: fetch\tSource: socket closed"
+        );
+        assert_eq!(err.display_with_separator(" | "), err.to_string());
+    }
+}
@@ -0,0 +1,199 @@
+//! Sampling and rate limiting to keep high-volume errors in check.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::{Error, Severity};
+
+fn slot(severity: Severity) -> usize {
+    match severity {
+        Severity::Critical => 0,
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Info => 3,
+    }
+}
+
+/// Decides which errors get logged, keeping 1 in N `Warning`/`Info` errors.
+///
+/// `Critical` and `Error` are always logged. Sampling is driven by a
+/// per-severity counter, so the same sequence of errors always yields the
+/// same decisions: the first error of a severity is logged, then every Nth.
+#[derive(Debug, Clone)]
+pub struct ErrorSampler {
+    rates: [u64; 4],
+    counters: [u64; 4],
+}
+
+impl ErrorSampler {
+    /// Creates a sampler that logs every error.
+    pub fn new() -> Self {
+        Self {
+            rates: [1; 4],
+            counters: [0; 4],
+        }
+    }
+
+    /// Logs 1 in `one_in` errors of `severity` (`0` is treated as `1`).
+    ///
+    /// Has no effect for `Critical` and `Error`, which are never sampled out.
+    pub fn with_rate(mut self, severity: Severity, one_in: u64) -> Self {
+        if matches!(severity, Severity::Warning | Severity::Info) {
+            self.rates[slot(severity)] = one_in.max(1);
+        }
+        self
+    }
+
+    /// Records `err` and returns whether it should be logged.
+    pub fn should_log(&mut self, err: &Error) -> bool {
+        let slot = slot(err.severity);
+        let count = self.counters[slot];
+        self.counters[slot] = count.wrapping_add(1);
+        count.is_multiple_of(self.rates[slot])
+    }
+}
+
+impl Default for ErrorSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Token-bucket limiter keyed by reference, protecting a sink from a flood
+/// of the same error.
+///
+/// Each reference gets its own bucket of `burst` tokens that refills at
+/// `burst` tokens per `window`; every allowed error spends one token.
+#[derive(Debug, Clone)]
+pub struct ReferenceRateLimiter {
+    burst: u32,
+    window: Duration,
+    buckets: HashMap<String, Bucket>,
+}
+
+#[derive(Debug, Clone)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl ReferenceRateLimiter {
+    /// Allows up to `burst` errors per reference within `window`
+    /// (`0` is treated as `1`).
+    pub fn new(burst: u32, window: Duration) -> Self {
+        Self {
+            burst: burst.max(1),
+            window,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Records `err` now and returns whether it is within its reference's rate.
+    pub fn allow(&mut self, err: &Error) -> bool {
+        self.allow_at(err, Instant::now())
+    }
+
+    /// Like `allow`, with the current time supplied by the caller, e.g. a
+    /// shared clock or a fixed instant in tests. Times before a previous call
+    /// for the same reference add no tokens.
+    pub fn allow_at(&mut self, err: &Error, now: Instant) -> bool {
+        let burst = f64::from(self.burst);
+        let bucket = self
+            .buckets
+            .entry(err.reference.clone())
+            .or_insert(Bucket {
+                tokens: burst,
+                updated: now,
+            });
+
+        let elapsed = now.saturating_duration_since(bucket.updated);
+        if !elapsed.is_zero() {
+            let refill = if self.window.is_zero() {
+                burst
+            } else {
+                burst * elapsed.as_secs_f64() / self.window.as_secs_f64()
+            };
+            bucket.tokens = (bucket.tokens + refill).min(burst);
+            bucket.updated = now;
+        }
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critical_and_error_always_pass() {
+        let mut sampler = ErrorSampler::new()
+            .with_rate(Severity::Critical, 10)
+            .with_rate(Severity::Error, 10);
+        let crit = Error::new(Severity::Critical, "SYS-001", "Out of memory");
+        let err = Error::new(Severity::Error, "NET-001", "Timeout");
+
+        assert!((0..100).all(|_| sampler.should_log(&crit)));
+        assert!((0..100).all(|_| sampler.should_log(&err)));
+    }
+
+    #[test]
+    fn info_sampled_one_in_n() {
+        let mut sampler = ErrorSampler::new().with_rate(Severity::Info, 10);
+        let info = Error::new(Severity::Info, "NET-100", "Retrying");
+
+        let logged = (0..1000).filter(|_| sampler.should_log(&info)).count();
+        assert_eq!(logged, 100);
+    }
+
+    #[test]
+    fn severities_are_counted_independently() {
+        let mut sampler = ErrorSampler::new()
+            .with_rate(Severity::Info, 3)
+            .with_rate(Severity::Warning, 2);
+        let info = Error::new(Severity::Info, "A", "a");
+        let warn = Error::new(Severity::Warning, "B", "b");
+
+        let decisions: Vec<bool> = (0..3)
+            .flat_map(|_| [sampler.should_log(&info), sampler.should_log(&warn)])
+            .collect();
+        assert_eq!(decisions, [true, true, false, false, false, true]);
+    }
+
+    #[test]
+    fn rate_limiter_passes_burst_then_drops() {
+        let mut limiter = ReferenceRateLimiter::new(3, Duration::from_secs(60));
+        let err = Error::new(Severity::Error, "NET-001", "Timeout");
+        let start = Instant::now();
+
+        let decisions: Vec<bool> = (0..5).map(|_| limiter.allow_at(&err, start)).collect();
+        assert_eq!(decisions, [true, true, true, false, false]);
+
+        // One token comes back every 20 seconds.
+        assert!(!limiter.allow_at(&err, start + Duration::from_secs(10)));
+        assert!(limiter.allow_at(&err, start + Duration::from_secs(20)));
+        assert!(!limiter.allow_at(&err, start + Duration::from_secs(21)));
+
+        // A full window refills the bucket, but never beyond the burst.
+        let later = start + Duration::from_secs(600);
+        let passed = (0..5).filter(|_| limiter.allow_at(&err, later)).count();
+        assert_eq!(passed, 3);
+    }
+
+    #[test]
+    fn rate_limiter_buckets_are_per_reference() {
+        let mut limiter = ReferenceRateLimiter::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+        let a = Error::new(Severity::Error, "NET-001", "Timeout");
+        let b = Error::new(Severity::Error, "NET-002", "Refused");
+
+        assert!(limiter.allow_at(&a, now));
+        assert!(!limiter.allow_at(&a, now));
+        assert!(limiter.allow_at(&b, now));
+    }
+}
@@ -0,0 +1,99 @@
+//! JSON Schema of the serialized `Error` (`schema` feature).
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+
+use crate::{Error, FieldValue, Severity};
+
+/// Mirror of the JSON form of `Error`, whose custom (de)serializers
+/// schemars cannot see through.
+#[derive(JsonSchema)]
+#[schemars(rename = "Error")]
+#[allow(dead_code)]
+struct ErrorSchema {
+    severity: Severity,
+    reference: String,
+    description: String,
+    metadata: HashMap<String, String>,
+    #[serde(default)]
+    fields: HashMap<String, FieldValue>,
+    /// Seconds before the caller may retry.
+    #[cfg(not(feature = "human-durations"))]
+    retry_after: Option<f64>,
+    #[cfg(feature = "human-durations")]
+    retry_after: Option<HumanDurationSchema>,
+    /// Creation time in Unix milliseconds.
+    timestamp: Option<u64>,
+    #[serde(default)]
+    context: Vec<String>,
+    docs_url: Option<String>,
+    http_status: Option<u16>,
+    fingerprint: Option<String>,
+    source: Option<SourceSchema>,
+}
+
+/// A duration in milliseconds with a human-readable rendering.
+#[cfg(feature = "human-durations")]
+#[derive(JsonSchema)]
+#[schemars(rename = "HumanDuration")]
+#[allow(dead_code)]
+struct HumanDurationSchema {
+    ms: u64,
+    /// E.g. `"250ms"`, `"1.5s"` or `"2m 5s"`.
+    human: String,
+}
+
+/// One level of the serialized source chain.
+#[derive(JsonSchema)]
+#[schemars(rename = "Source")]
+#[allow(dead_code)]
+struct SourceSchema {
+    /// Concrete type of the source, for well-known types only.
+    #[serde(rename = "type")]
+    type_name: Option<String>,
+    message: String,
+    source: Option<Box<SourceSchema>>,
+}
+
+impl Error {
+    /// Returns the JSON Schema of the serialized form, for consumers in other
+    /// languages.
+    pub fn json_schema() -> serde_json::Value {
+        schemars::schema_for!(ErrorSchema).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_lists_required_properties() {
+        let schema = Error::json_schema();
+        let required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        for property in ["severity", "reference", "description", "metadata"] {
+            assert!(required.contains(&property), "{property} missing from {required:?}");
+        }
+        assert!(!required.contains(&"source"));
+        assert!(schema["properties"]["source"].is_object());
+        assert!(schema["$defs"]["Source"]["properties"]["message"].is_object());
+    }
+
+    #[test]
+    fn schema_lists_severity_values() {
+        let schema = Error::json_schema();
+        let values = &schema["$defs"]["Severity"]["enum"];
+        let expected: Vec<serde_json::Value> =
+            [Severity::Critical, Severity::Error, Severity::Warning, Severity::Info]
+                .iter()
+                .map(|severity| serde_json::to_value(severity).unwrap())
+                .collect();
+        assert_eq!(values.as_array().unwrap(), &expected);
+    }
+}
@@ -0,0 +1,79 @@
+//! Writers that forward serialized errors to an underlying sink.
+
+use std::io::{self, Write};
+
+use crate::{Error, Severity};
+
+/// Writes errors as JSON lines to `inner`, dropping those below `min`.
+///
+/// ```
+/// use mcp_error::{Error, Severity, SeverityFilterWriter};
+///
+/// let mut sink = SeverityFilterWriter::new(Vec::new(), Severity::Warning);
+/// sink.write_error(&Error::info("NET-001", "Connected")).unwrap();
+/// sink.write_error(&Error::warning("NET-002", "Slow response")).unwrap();
+/// let out = String::from_utf8(sink.into_inner()).unwrap();
+/// assert_eq!(out.lines().count(), 1);
+/// ```
+#[derive(Debug)]
+pub struct SeverityFilterWriter<W> {
+    inner: W,
+    min: Severity,
+}
+
+impl<W: Write> SeverityFilterWriter<W> {
+    /// Forwards errors of severity `min` and above to `inner`.
+    pub fn new(inner: W, min: Severity) -> Self {
+        Self { inner, min }
+    }
+
+    /// Serializes `err` followed by a newline if `err.severity >= min`;
+    /// errors below the threshold are silently skipped.
+    pub fn write_error(&mut self, err: &Error) -> io::Result<()> {
+        if err.severity < self.min {
+            return Ok(());
+        }
+        serde_json::to_writer(&mut self.inner, err)?;
+        self.inner.write_all(b"\n")
+    }
+
+    /// Returns the threshold.
+    pub fn min(&self) -> Severity {
+        self.min
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Unwraps the filter, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_only_at_or_above_threshold() {
+        let mut sink = SeverityFilterWriter::new(Vec::new(), Severity::Error);
+        for err in [
+            Error::info("NET-001", "Connected"),
+            Error::critical("DS-001", "Index corrupted"),
+            Error::warning("NET-002", "Slow response"),
+            Error::error("NET-003", "Timeout"),
+        ] {
+            sink.write_error(&err).unwrap();
+        }
+
+        let out = String::from_utf8(sink.into_inner()).unwrap();
+        let refs: Vec<String> = out
+            .lines()
+            .map(|line| Error::from_json(line).unwrap().reference)
+            .collect();
+        assert_eq!(refs, ["DS-001", "NET-003"]);
+    }
+}
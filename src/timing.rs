@@ -0,0 +1,81 @@
+//! Attaching operation latency to errors.
+
+use std::time::{Duration, Instant};
+
+use crate::Error;
+
+/// Metadata key set by `Error::with_elapsed`.
+pub const ELAPSED_MS_KEY: &str = "elapsed_ms";
+
+/// Measures an operation so a resulting error can carry its duration.
+///
+/// ```
+/// use mcp_error::{Error, Stopwatch};
+///
+/// let watch = Stopwatch::start();
+/// let err = watch.stamp(Error::error("NET-001", "Timeout"));
+/// assert!(err.metadata.contains_key("elapsed_ms"));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch {
+    start: Instant,
+}
+
+impl Stopwatch {
+    /// Starts measuring now.
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns when the stopwatch was started.
+    pub fn started_at(&self) -> Instant {
+        self.start
+    }
+
+    /// Returns the time elapsed since the start.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Records the elapsed time on `err`, see `Error::with_elapsed`.
+    pub fn stamp(&self, err: Error) -> Error {
+        err.with_elapsed(self.start)
+    }
+}
+
+impl Error {
+    /// Stores the whole milliseconds elapsed since `start` in the
+    /// `elapsed_ms` metadata entry.
+    pub fn with_elapsed(self, start: Instant) -> Self {
+        let elapsed_ms = start.elapsed().as_millis();
+        self.insert_metadata(ELAPSED_MS_KEY, elapsed_ms.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elapsed_ms_is_plausible() {
+        let start = Instant::now();
+        std::thread::sleep(Duration::from_millis(20));
+        let err = Error::error("NET-001", "Timeout").with_elapsed(start);
+
+        let elapsed_ms: u64 = err.metadata[ELAPSED_MS_KEY].parse().unwrap();
+        assert!((20..10_000).contains(&elapsed_ms), "{elapsed_ms}");
+    }
+
+    #[test]
+    fn stopwatch_stamps_errors() {
+        let watch = Stopwatch::start();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(watch.elapsed() >= Duration::from_millis(5));
+
+        let err = watch.stamp(Error::error("NET-001", "Timeout"));
+        let elapsed_ms: u64 = err.metadata[ELAPSED_MS_KEY].parse().unwrap();
+        assert!(elapsed_ms >= 5);
+    }
+}
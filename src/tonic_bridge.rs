@@ -0,0 +1,112 @@
+//! Conversion into `tonic::Status` for gRPC services (`tonic` feature).
+
+use std::io;
+
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::{Code, Status};
+
+use crate::Error;
+
+/// Picks the gRPC code for `err`.
+///
+/// The first `io::Error` in the chain decides when its kind has a natural
+/// counterpart; otherwise the reference is matched against `TIMEOUT` and
+/// `NOT-FOUND`/`NOTFOUND`/`404`. Anything else is `Internal`.
+fn grpc_code(err: &Error) -> Code {
+    let from_io = err.chain_find::<io::Error>().and_then(|io_err| match io_err.kind() {
+        io::ErrorKind::TimedOut => Some(Code::DeadlineExceeded),
+        io::ErrorKind::NotFound => Some(Code::NotFound),
+        io::ErrorKind::PermissionDenied => Some(Code::PermissionDenied),
+        io::ErrorKind::AlreadyExists => Some(Code::AlreadyExists),
+        io::ErrorKind::InvalidInput | io::ErrorKind::InvalidData => Some(Code::InvalidArgument),
+        io::ErrorKind::ConnectionRefused
+        | io::ErrorKind::ConnectionReset
+        | io::ErrorKind::ConnectionAborted
+        | io::ErrorKind::NotConnected => Some(Code::Unavailable),
+        io::ErrorKind::Unsupported => Some(Code::Unimplemented),
+        _ => None,
+    });
+    if let Some(code) = from_io {
+        return code;
+    }
+
+    let reference = err.reference.to_ascii_uppercase();
+    if reference.contains("TIMEOUT") {
+        Code::DeadlineExceeded
+    } else if reference.contains("NOT-FOUND")
+        || reference.contains("NOTFOUND")
+        || reference.ends_with("404")
+    {
+        Code::NotFound
+    } else {
+        Code::Internal
+    }
+}
+
+/// Maps the error to a `Status` with the description as message.
+///
+/// The reference and severity travel as `mcp-reference` and `mcp-severity`
+/// metadata, followed by the error's own metadata. Entries that are not valid
+/// ASCII gRPC metadata are dropped.
+impl From<Error> for Status {
+    fn from(err: Error) -> Self {
+        let mut metadata = MetadataMap::new();
+        let own = [
+            ("mcp-reference".to_string(), err.reference.clone()),
+            ("mcp-severity".to_string(), err.severity.to_string()),
+        ];
+        for (key, value) in own.into_iter().chain(err.metadata.clone()) {
+            if key.ends_with("-bin") {
+                continue;
+            }
+            if let (Ok(key), Ok(value)) = (
+                key.to_ascii_lowercase().parse::<tonic::metadata::AsciiMetadataKey>(),
+                MetadataValue::try_from(value.as_str()),
+            ) {
+                metadata.insert(key, value);
+            }
+        }
+        Status::with_metadata(grpc_code(&err), err.description, metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Severity;
+
+    #[test]
+    fn code_from_reference() {
+        let err = Error::new(Severity::Error, "NET-TIMEOUT", "Upstream timed out");
+        let status = Status::from(err);
+        assert_eq!(status.code(), Code::DeadlineExceeded);
+        assert_eq!(status.message(), "Upstream timed out");
+
+        let status = Status::from(Error::new(Severity::Error, "FSY-404", "No such file"));
+        assert_eq!(status.code(), Code::NotFound);
+
+        let status = Status::from(Error::new(Severity::Critical, "DS-001", "Index corrupted"));
+        assert_eq!(status.code(), Code::Internal);
+    }
+
+    #[test]
+    fn code_from_io_kind_wins() {
+        let err = Error::new(Severity::Error, "NET-001", "Cannot connect")
+            .with_source(Box::new(io::Error::from(io::ErrorKind::ConnectionRefused)));
+        assert_eq!(Status::from(err).code(), Code::Unavailable);
+    }
+
+    #[test]
+    fn metadata_is_forwarded() {
+        let err = Error::new(Severity::Warning, "FSY-404", "No such file")
+            .insert_metadata("Filename", "config.toml")
+            .insert_metadata("bad key", "dropped")
+            .insert_metadata("blob-bin", "dropped");
+        let status = Status::from(err);
+        let metadata = status.metadata();
+        assert_eq!(metadata.get("mcp-reference").unwrap(), "FSY-404");
+        assert_eq!(metadata.get("mcp-severity").unwrap(), "WARN");
+        assert_eq!(metadata.get("filename").unwrap(), "config.toml");
+        assert_eq!(metadata.len(), 3);
+    }
+}